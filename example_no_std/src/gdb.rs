@@ -35,7 +35,7 @@ impl MultiThreadOps for DummyTarget {
     #[inline(never)]
     fn resume(
         &mut self,
-        _actions: Actions,
+        _actions: Actions<u32>,
         _check_gdb_interrupt: &mut dyn FnMut() -> bool,
     ) -> Result<ThreadStopReason<u32>, Self::Error> {
         print_str("> resume");
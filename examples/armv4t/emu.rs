@@ -1,5 +1,8 @@
 use armv4t_emu::{reg, Cpu, ExampleMem, Memory, Mode};
+use gdbstub::target::ext::file_io::Syscall;
 
+use crate::gdb::branch_trace::BranchTraceState;
+use crate::gdb::tracepoints::TraceState;
 use crate::mem_sniffer::{AccessKind, MemSniffer};
 use crate::DynResult;
 
@@ -11,6 +14,7 @@ pub enum Event {
     Break,
     WatchWrite(u32),
     WatchRead(u32),
+    Syscall(Syscall<u32>),
 }
 
 /// incredibly barebones armv4t-based emulator
@@ -22,6 +26,13 @@ pub struct Emu {
 
     pub(crate) watchpoints: Vec<u32>,
     pub(crate) breakpoints: Vec<u32>,
+
+    pub(crate) tracing: TraceState,
+    pub(crate) btrace: Option<BranchTraceState>,
+
+    pub(crate) files: Vec<Option<std::fs::File>>,
+
+    pub(crate) syscall_complete: bool,
 }
 
 impl Emu {
@@ -65,6 +76,10 @@ impl Emu {
             mem,
             watchpoints: Vec::new(),
             breakpoints: Vec::new(),
+            tracing: TraceState::new(),
+            btrace: None,
+            files: Vec::new(),
+            syscall_complete: false,
         })
     }
 
@@ -77,14 +92,30 @@ impl Emu {
 
     pub fn step(&mut self) -> Option<Event> {
         let mut hit_watchpoint = None;
+        let prev_pc = self.cpu.reg_get(Mode::User, reg::PC);
+
+        if self.syscall_complete {
+            // GDB already performed the syscall, so just skip over the `swi` instruction
+            self.syscall_complete = false;
+            let pc = self.cpu.reg_get(Mode::User, reg::PC);
+            self.cpu.reg_set(Mode::User, reg::PC, pc + 4);
+        } else if let Some(syscall) = self.syscall_request() {
+            return Some(Event::Syscall(syscall));
+        } else {
+            let mut sniffer = MemSniffer::new(&mut self.mem, &self.watchpoints, |access| {
+                hit_watchpoint = Some(access)
+            });
 
-        let mut sniffer = MemSniffer::new(&mut self.mem, &self.watchpoints, |access| {
-            hit_watchpoint = Some(access)
-        });
+            self.cpu.step(&mut sniffer);
+        }
 
-        self.cpu.step(&mut sniffer);
         let pc = self.cpu.reg_get(Mode::User, reg::PC);
 
+        self.trace_step();
+        if let Some(btrace) = &mut self.btrace {
+            btrace.record(prev_pc, pc);
+        }
+
         if let Some(access) = hit_watchpoint {
             let fixup = if self.cpu.thumb_mode() { 2 } else { 4 };
             self.cpu.reg_set(Mode::User, reg::PC, pc - fixup);
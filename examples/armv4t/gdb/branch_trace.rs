@@ -0,0 +1,104 @@
+use armv4t_emu::{reg, Mode};
+use gdbstub::common::{Pid, Tid};
+use gdbstub::target;
+use gdbstub::target::ext::branch_trace::{BtraceReadType, BtsConfig};
+use gdbstub::target::TargetResult;
+
+use crate::gdb::Emu;
+
+/// Branch trace recorded by the emulator.
+pub struct BranchTraceState {
+    /// Completed blocks (oldest first).
+    blocks: Vec<(u32, u32)>,
+    /// Start of the block currently being executed.
+    block_start: u32,
+    /// Number of completed blocks at the time of the previous read.
+    last_read: Option<(usize, u32)>,
+    /// Blocks to report to GDB (newest first).
+    snapshot: Vec<(u32, u32)>,
+}
+
+impl BranchTraceState {
+    /// Record the execution of a single instruction.
+    pub fn record(&mut self, pc: u32, next_pc: u32) {
+        // the emulator only runs ARM code, so any other change to the PC must
+        // be the result of a taken branch.
+        if next_pc != pc.wrapping_add(4) {
+            self.blocks.push((self.block_start, pc));
+            self.block_start = next_pc;
+        }
+    }
+}
+
+impl target::ext::branch_trace::BranchTrace for Emu {
+    fn enable_btrace(&mut self, _pid: Pid, _tid: Tid) -> TargetResult<(), Self> {
+        if self.btrace.is_some() {
+            return Err(().into());
+        }
+
+        self.btrace = Some(BranchTraceState {
+            blocks: Vec::new(),
+            block_start: self.cpu.reg_get(Mode::User, reg::PC),
+            last_read: None,
+            snapshot: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn disable_btrace(&mut self, _pid: Pid, _tid: Tid) -> TargetResult<(), Self> {
+        match self.btrace.take() {
+            Some(_) => Ok(()),
+            None => Err(().into()),
+        }
+    }
+
+    fn btrace_config(&mut self, _pid: Pid, _tid: Tid) -> TargetResult<Option<BtsConfig>, Self> {
+        // the emulator's trace buffer is unbounded
+        Ok(self.btrace.as_ref().map(|_| BtsConfig { size: None }))
+    }
+
+    fn capture_btrace(
+        &mut self,
+        _pid: Pid,
+        _tid: Tid,
+        read_type: BtraceReadType,
+    ) -> TargetResult<(), Self> {
+        let pc = self.cpu.reg_get(Mode::User, reg::PC);
+        let trace = self.btrace.as_mut().ok_or(())?;
+
+        let start = match read_type {
+            BtraceReadType::All => 0,
+            BtraceReadType::New if trace.last_read == Some((trace.blocks.len(), pc)) => {
+                trace.snapshot.clear();
+                return Ok(());
+            }
+            BtraceReadType::New => 0,
+            BtraceReadType::Delta => match trace.last_read {
+                Some((n, _)) => n,
+                None => return Err(().into()),
+            },
+        };
+
+        trace.snapshot.clear();
+        trace.snapshot.push((trace.block_start, pc));
+        trace
+            .snapshot
+            .extend(trace.blocks[start..].iter().rev().copied());
+        trace.last_read = Some((trace.blocks.len(), pc));
+
+        Ok(())
+    }
+
+    fn read_btrace(
+        &mut self,
+        _pid: Pid,
+        _tid: Tid,
+        register_block: &mut dyn FnMut(u32, u32),
+    ) -> TargetResult<(), Self> {
+        let trace = self.btrace.as_ref().ok_or(())?;
+        for &(begin, end) in trace.snapshot.iter() {
+            register_block(begin, end);
+        }
+        Ok(())
+    }
+}
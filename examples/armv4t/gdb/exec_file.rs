@@ -0,0 +1,17 @@
+use gdbstub::common::Pid;
+use gdbstub::target;
+use gdbstub::target::TargetResult;
+
+use crate::emu::Emu;
+
+impl target::ext::exec_file::ExecFile for Emu {
+    fn get_exec_file(&mut self, _pid: Pid) -> TargetResult<&[u8], Self> {
+        // Host I/O requests are forwarded to the host's filesystem, so GDB can
+        // load the test program directly from the source tree.
+        Ok(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/armv4t/test_bin/test.elf"
+        )
+        .as_bytes())
+    }
+}
@@ -31,8 +31,9 @@ impl target::ext::extended_mode::ExtendedMode for Emu {
     }
 
     fn attach(&mut self, pid: Pid) -> TargetResult<(), Self> {
-        eprintln!("GDB tried to attach to a process with PID {}", pid);
-        Err(().into()) // non-specific failure
+        eprintln!("GDB attached to a process with PID {}", pid);
+        // "attaching" simply leaves the currently running program as-is
+        Ok(())
     }
 
     fn run(&mut self, filename: Option<&[u8]>, args: Args) -> TargetResult<Pid, Self> {
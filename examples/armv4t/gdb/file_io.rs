@@ -0,0 +1,94 @@
+use armv4t_emu::{reg, Memory};
+use gdbstub::target;
+use gdbstub::target::ext::file_io::{SeekWhence, Syscall, SyscallResult};
+use gdbstub::target::ext::host_io::{HostIoErrno, HostIoOpenFlags, HostIoOpenMode};
+use gdbstub::target::TargetResult;
+
+use crate::gdb::Emu;
+
+// The emulated system exposes a handful of File-I/O syscalls to the guest via
+// the `swi` instruction, with the syscall number encoded in the instruction's
+// comment field, arguments passed in r0-r3, and the result returned in r0
+// (using negative errno values to report errors).
+//
+// Upon hitting a `swi` instruction, the emulator stops with a syscall request
+// _without_ executing the instruction. Once GDB replies with the syscall's
+// result, the instruction is retired by the next call to `Emu::step`.
+
+impl Emu {
+    /// Check if the instruction at the current PC requests a syscall.
+    pub(crate) fn syscall_request(&mut self) -> Option<Syscall<u32>> {
+        if self.cpu.thumb_mode() {
+            return None;
+        }
+
+        let mode = self.cpu.mode();
+        let pc = self.cpu.reg_get(mode, reg::PC);
+        let insn = self.mem.r32(pc);
+        // only unconditional `swi` instructions
+        if insn & 0xff00_0000 != 0xef00_0000 {
+            return None;
+        }
+
+        let r = |i| self.cpu.reg_get(mode, i);
+        let syscall = match insn & 0x00ff_ffff {
+            1 => Syscall::Open {
+                path: r(0),
+                path_len: r(1),
+                flags: HostIoOpenFlags(r(2)),
+                mode: HostIoOpenMode(r(3)),
+            },
+            2 => Syscall::Close { fd: r(0) },
+            3 => Syscall::Read {
+                fd: r(0),
+                buf: r(1),
+                count: r(2),
+            },
+            4 => Syscall::Write {
+                fd: r(0),
+                buf: r(1),
+                count: r(2),
+            },
+            5 => Syscall::Lseek {
+                fd: r(0),
+                offset: r(1) as i32 as i64,
+                whence: match r(2) {
+                    0 => SeekWhence::Set,
+                    1 => SeekWhence::Cur,
+                    2 => SeekWhence::End,
+                    _ => return None,
+                },
+            },
+            6 => Syscall::Unlink {
+                path: r(0),
+                path_len: r(1),
+            },
+            7 => Syscall::IsATty { fd: r(0) },
+            8 => Syscall::GetTimeOfDay { tv: r(0), tz: r(1) },
+            _ => return None,
+        };
+
+        Some(syscall)
+    }
+}
+
+impl target::ext::file_io::FileIo for Emu {
+    fn complete_syscall(&mut self, result: SyscallResult) -> TargetResult<(), Self> {
+        // the syscall wasn't performed, so leave the PC pointing at the `swi`
+        // instruction, and retry the syscall once the emulator is resumed.
+        if result.interrupted && result.errno == Some(HostIoErrno::EINTR) {
+            return Ok(());
+        }
+
+        let ret = match result.errno {
+            Some(errno) if result.retcode == -1 => -(errno as i32),
+            _ => result.retcode as i32,
+        };
+        self.cpu.reg_set(self.cpu.mode(), 0, ret as u32);
+
+        // the `swi` instruction is retired the next time the emulator is stepped
+        self.syscall_complete = true;
+
+        Ok(())
+    }
+}
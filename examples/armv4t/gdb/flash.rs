@@ -0,0 +1,56 @@
+use armv4t_emu::Memory;
+use gdbstub::target;
+use gdbstub::target::ext::flash::FlashWriteError;
+use gdbstub::target::{TargetError, TargetResult};
+
+use crate::gdb::Emu;
+
+// Must match the flash region in the memory map (see `memory_map.rs`)
+const FLASH_START: u32 = 0x0800_0000;
+const FLASH_LEN: u32 = 0x0010_0000;
+const FLASH_BLOCK_SIZE: u32 = 0x1000;
+
+fn in_flash(start_addr: u32, len: u32) -> bool {
+    start_addr >= FLASH_START
+        && matches!(start_addr.checked_add(len), Some(end) if end <= FLASH_START + FLASH_LEN)
+}
+
+// The flash is modeled as NOR flash, where erasing sets all bits in a block to
+// 1, and writing can only clear bits.
+
+impl target::ext::flash::Flash for Emu {
+    fn flash_erase(&mut self, start_addr: u32, length: u32) -> TargetResult<(), Self> {
+        if !in_flash(start_addr, length)
+            || start_addr % FLASH_BLOCK_SIZE != 0
+            || length % FLASH_BLOCK_SIZE != 0
+        {
+            return Err(TargetError::Errno(22)); // EINVAL
+        }
+
+        for addr in start_addr..start_addr + length {
+            self.mem.w8(addr, 0xff);
+        }
+        Ok(())
+    }
+
+    fn flash_write(
+        &mut self,
+        start_addr: u32,
+        data: &[u8],
+    ) -> Result<(), FlashWriteError<&'static str>> {
+        if !in_flash(start_addr, data.len() as u32) {
+            return Err(FlashWriteError::NotFlash);
+        }
+
+        for (addr, val) in (start_addr..).zip(data.iter().copied()) {
+            let old = self.mem.r8(addr);
+            self.mem.w8(addr, old & val);
+        }
+        Ok(())
+    }
+
+    fn flash_done(&mut self) -> TargetResult<(), Self> {
+        // writes are applied immediately, so there's nothing to commit
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::{Read, Seek, Write};
+
+use gdbstub::target;
+use gdbstub::target::ext::host_io::{
+    FsKind, HostIoErrno, HostIoError, HostIoOpenFlags, HostIoOpenMode, HostIoResult, HostIoStat,
+};
+
+use crate::gdb::Emu;
+
+// The emulated system doesn't have it's own filesystem, so Host I/O operations
+// are forwarded to the host's filesystem instead.
+
+impl target::ext::host_io::HostIo for Emu {
+    fn support_open(&mut self) -> Option<target::ext::host_io::HostIoOpenOps<Self>> {
+        Some(self)
+    }
+
+    fn support_close(&mut self) -> Option<target::ext::host_io::HostIoCloseOps<Self>> {
+        Some(self)
+    }
+
+    fn support_pread(&mut self) -> Option<target::ext::host_io::HostIoPreadOps<Self>> {
+        Some(self)
+    }
+
+    fn support_pwrite(&mut self) -> Option<target::ext::host_io::HostIoPwriteOps<Self>> {
+        Some(self)
+    }
+
+    fn support_fstat(&mut self) -> Option<target::ext::host_io::HostIoFstatOps<Self>> {
+        Some(self)
+    }
+
+    fn support_unlink(&mut self) -> Option<target::ext::host_io::HostIoUnlinkOps<Self>> {
+        Some(self)
+    }
+
+    fn support_readlink(&mut self) -> Option<target::ext::host_io::HostIoReadlinkOps<Self>> {
+        Some(self)
+    }
+
+    fn support_setfs(&mut self) -> Option<target::ext::host_io::HostIoSetfsOps<Self>> {
+        Some(self)
+    }
+}
+
+fn to_path(filename: &[u8]) -> HostIoResult<&str, Emu> {
+    std::str::from_utf8(filename).map_err(|_| HostIoError::Errno(HostIoErrno::ENOENT))
+}
+
+impl Emu {
+    fn file(&mut self, fd: u32) -> HostIoResult<&mut std::fs::File, Self> {
+        match self.files.get_mut(fd as usize) {
+            Some(Some(file)) => Ok(file),
+            _ => Err(HostIoError::Errno(HostIoErrno::EBADF)),
+        }
+    }
+}
+
+impl target::ext::host_io::HostIoOpen for Emu {
+    fn open(
+        &mut self,
+        filename: &[u8],
+        flags: HostIoOpenFlags,
+        _mode: HostIoOpenMode,
+    ) -> HostIoResult<u32, Self> {
+        let path = to_path(filename)?;
+
+        let mut options = std::fs::OpenOptions::new();
+        match flags.access_mode() {
+            HostIoOpenFlags::O_RDONLY => options.read(true),
+            HostIoOpenFlags::O_WRONLY => options.write(true),
+            HostIoOpenFlags::O_RDWR => options.read(true).write(true),
+            _ => return Err(HostIoError::Errno(HostIoErrno::EINVAL)),
+        };
+        options
+            .append(flags.contains(HostIoOpenFlags::O_APPEND))
+            .truncate(flags.contains(HostIoOpenFlags::O_TRUNC));
+        if flags.contains(HostIoOpenFlags::O_CREAT) {
+            if flags.contains(HostIoOpenFlags::O_EXCL) {
+                options.create_new(true);
+            } else {
+                options.create(true);
+            }
+        }
+
+        let file = options.open(path)?;
+
+        // reuse the first free file descriptor
+        let fd = match self.files.iter().position(|f| f.is_none()) {
+            Some(fd) => {
+                self.files[fd] = Some(file);
+                fd
+            }
+            None => {
+                self.files.push(Some(file));
+                self.files.len() - 1
+            }
+        };
+
+        Ok(fd as u32)
+    }
+}
+
+impl target::ext::host_io::HostIoClose for Emu {
+    fn close(&mut self, fd: u32) -> HostIoResult<(), Self> {
+        match self.files.get_mut(fd as usize).and_then(Option::take) {
+            Some(_) => Ok(()),
+            None => Err(HostIoError::Errno(HostIoErrno::EBADF)),
+        }
+    }
+}
+
+impl target::ext::host_io::HostIoPread for Emu {
+    fn pread(
+        &mut self,
+        fd: u32,
+        count: usize,
+        offset: u64,
+        buf: &mut [u8],
+    ) -> HostIoResult<usize, Self> {
+        let file = self.file(fd)?;
+        file.seek(std::io::SeekFrom::Start(offset))?;
+        Ok(file.read(&mut buf[..count])?)
+    }
+}
+
+impl target::ext::host_io::HostIoPwrite for Emu {
+    fn pwrite(&mut self, fd: u32, offset: u64, data: &[u8]) -> HostIoResult<usize, Self> {
+        let file = self.file(fd)?;
+        file.seek(std::io::SeekFrom::Start(offset))?;
+        Ok(file.write(data)?)
+    }
+}
+
+impl target::ext::host_io::HostIoFstat for Emu {
+    fn fstat(&mut self, fd: u32) -> HostIoResult<HostIoStat, Self> {
+        let metadata = self.file(fd)?.metadata()?;
+
+        let mode = if metadata.is_dir() {
+            HostIoOpenMode::S_IFDIR
+        } else {
+            HostIoOpenMode::S_IFREG
+        };
+        let perms = if metadata.permissions().readonly() {
+            HostIoOpenMode::S_IRUSR | HostIoOpenMode::S_IRGRP | HostIoOpenMode::S_IROTH
+        } else {
+            HostIoOpenMode::S_IRUSR | HostIoOpenMode::S_IWUSR
+        };
+
+        let secs = |time: std::io::Result<std::time::SystemTime>| {
+            time.ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as u32)
+                .unwrap_or(0)
+        };
+
+        Ok(HostIoStat {
+            st_dev: 0,
+            st_ino: 0,
+            st_mode: HostIoOpenMode(mode | perms),
+            st_nlink: 1,
+            st_uid: 0,
+            st_gid: 0,
+            st_rdev: 0,
+            st_size: metadata.len(),
+            st_blksize: 0,
+            st_blocks: 0,
+            st_atime: secs(metadata.accessed()),
+            st_mtime: secs(metadata.modified()),
+            st_ctime: secs(metadata.created()),
+        })
+    }
+}
+
+impl target::ext::host_io::HostIoUnlink for Emu {
+    fn unlink(&mut self, filename: &[u8]) -> HostIoResult<(), Self> {
+        Ok(std::fs::remove_file(to_path(filename)?)?)
+    }
+}
+
+impl target::ext::host_io::HostIoReadlink for Emu {
+    fn readlink(&mut self, filename: &[u8], buf: &mut [u8]) -> HostIoResult<usize, Self> {
+        let link = std::fs::read_link(to_path(filename)?)?;
+        let link = link
+            .to_str()
+            .ok_or(HostIoError::Errno(HostIoErrno::ENOENT))?;
+
+        let link = link.as_bytes();
+        if link.len() > buf.len() {
+            return Err(HostIoError::Errno(HostIoErrno::ENAMETOOLONG));
+        }
+        buf[..link.len()].copy_from_slice(link);
+        Ok(link.len())
+    }
+}
+
+impl target::ext::host_io::HostIoSetfs for Emu {
+    fn setfs(&mut self, fs: FsKind) -> HostIoResult<(), Self> {
+        // the emulator only has a single process, which shares the stub's filesystem
+        match fs {
+            FsKind::Stub => Ok(()),
+            FsKind::Pid(pid) if pid.get() == 1 => Ok(()),
+            FsKind::Pid(_) => Err(HostIoError::Errno(HostIoErrno::EINVAL)),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use gdbstub::target;
+
+use crate::gdb::Emu;
+
+impl target::ext::memory_map::MemoryMap for Emu {
+    fn memory_map_xml(&self) -> &str {
+        // Sample memory map, with a single flash region (see `flash.rs`), and
+        // RAM covering the rest of the address space.
+        r#"<?xml version="1.0"?>
+<!DOCTYPE memory-map
+    PUBLIC "+//IDN gnu.org//DTD GDB Memory Map V1.0//EN"
+            "http://sourceware.org/gdb/gdb-memory-map.dtd">
+<memory-map>
+    <memory type="ram" start="0x0" length="0x8000000"/>
+    <memory type="flash" start="0x8000000" length="0x100000">
+        <property name="blocksize">0x1000</property>
+    </memory>
+    <memory type="ram" start="0x8100000" length="0xf7f00000"/>
+</memory-map>"#
+    }
+}
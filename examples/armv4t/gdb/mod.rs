@@ -12,9 +12,16 @@ use crate::emu::{Emu, Event};
 
 // Additional GDB extensions
 
+pub(crate) mod branch_trace;
+mod exec_file;
 mod extended_mode;
+mod file_io;
+mod flash;
+mod host_io;
+mod memory_map;
 mod monitor_cmd;
 mod section_offsets;
+pub(crate) mod tracepoints;
 
 /// Turn a `ArmCoreRegId` into an internal register number of `armv4t_emu`.
 fn cpu_reg_id(id: ArmCoreRegId) -> Option<u8> {
@@ -55,20 +62,50 @@ impl Target for Emu {
     fn section_offsets(&mut self) -> Option<target::ext::section_offsets::SectionOffsetsOps<Self>> {
         Some(self)
     }
+
+    fn file_io(&mut self) -> Option<target::ext::file_io::FileIoOps<Self>> {
+        Some(self)
+    }
+
+    fn memory_map(&mut self) -> Option<target::ext::memory_map::MemoryMapOps<Self>> {
+        Some(self)
+    }
+
+    fn flash(&mut self) -> Option<target::ext::flash::FlashOps<Self>> {
+        Some(self)
+    }
+
+    fn exec_file(&mut self) -> Option<target::ext::exec_file::ExecFileOps<Self>> {
+        Some(self)
+    }
+
+    fn branch_trace(&mut self) -> Option<target::ext::branch_trace::BranchTraceOps<Self>> {
+        Some(self)
+    }
+
+    fn host_io(&mut self) -> Option<target::ext::host_io::HostIoOps<Self>> {
+        Some(self)
+    }
+
+    fn tracepoints(&mut self) -> Option<target::ext::tracepoints::TracepointsOps<Self>> {
+        Some(self)
+    }
 }
 
 impl SingleThreadOps for Emu {
     fn resume(
         &mut self,
-        action: ResumeAction,
+        action: ResumeAction<u32>,
         check_gdb_interrupt: &mut dyn FnMut() -> bool,
     ) -> Result<StopReason<u32>, Self::Error> {
+        // the emulated system doesn't have a notion of signals, so resuming with a
+        // signal is treated the same as a plain step/continue.
         let event = match action {
-            ResumeAction::Step => match self.step() {
+            ResumeAction::Step | ResumeAction::StepWithSignal(_) => match self.step() {
                 Some(e) => e,
                 None => return Ok(StopReason::DoneStep),
             },
-            ResumeAction::Continue => {
+            ResumeAction::Continue | ResumeAction::ContinueWithSignal(_) => {
                 let mut cycles = 0;
                 loop {
                     if let Some(event) = self.step() {
@@ -82,6 +119,28 @@ impl SingleThreadOps for Emu {
                     }
                 }
             }
+            ResumeAction::StepInRange(start, end) => {
+                let mut cycles = 0;
+                loop {
+                    if let Some(event) = self.step() {
+                        break event;
+                    };
+
+                    let pc = self.cpu.reg_get(self.cpu.mode(), reg::PC);
+                    if !(start..end).contains(&pc) {
+                        return Ok(StopReason::DoneStep);
+                    }
+
+                    // check for GDB interrupt every 1024 instructions
+                    cycles += 1;
+                    if cycles % 1024 == 0 && check_gdb_interrupt() {
+                        return Ok(StopReason::GdbInterrupt);
+                    }
+                }
+            }
+            // any other actions (e.g: `Stop`, or reverse execution) are only sent to targets
+            // which explicitly opt-in to them
+            _ => return Err("unexpected resume action"),
         };
 
         Ok(match event {
@@ -95,10 +154,16 @@ impl SingleThreadOps for Emu {
                 kind: WatchKind::Read,
                 addr,
             },
+            Event::Syscall(syscall) => StopReason::SyscallRequest(syscall),
         })
     }
 
     fn read_registers(&mut self, regs: &mut arch::arm::reg::ArmCoreRegs) -> TargetResult<(), Self> {
+        if let Some(frame) = self.tracing.selected_frame() {
+            frame.read_registers(regs);
+            return Ok(());
+        }
+
         let mode = self.cpu.mode();
 
         for i in 0..13 {
@@ -132,7 +197,10 @@ impl SingleThreadOps for Emu {
         dst: &mut [u8],
     ) -> TargetResult<(), Self> {
         if let Some(i) = cpu_reg_id(reg_id) {
-            let w = self.cpu.reg_get(self.cpu.mode(), i);
+            let w = match self.tracing.selected_frame() {
+                Some(frame) => frame.read_register(i).ok_or(())?,
+                None => self.cpu.reg_get(self.cpu.mode(), i),
+            };
             dst.copy_from_slice(&w.to_le_bytes());
             Ok(())
         } else {
@@ -158,6 +226,13 @@ impl SingleThreadOps for Emu {
     }
 
     fn read_addrs(&mut self, start_addr: u32, data: &mut [u8]) -> TargetResult<(), Self> {
+        if let Some(frame) = self.tracing.selected_frame() {
+            return match frame.read_addrs(start_addr, data) {
+                true => Ok(()),
+                false => Err(().into()),
+            };
+        }
+
         for (addr, val) in (start_addr..).zip(data.iter_mut()) {
             *val = self.mem.r8(addr)
         }
@@ -170,8 +245,16 @@ impl SingleThreadOps for Emu {
         }
         Ok(())
     }
+
+    fn support_range_stepping(
+        &mut self,
+    ) -> Option<target::ext::base::singlethread::SingleThreadRangeSteppingOps<Self>> {
+        Some(self)
+    }
 }
 
+impl target::ext::base::singlethread::SingleThreadRangeStepping for Emu {}
+
 impl target::ext::breakpoints::SwBreakpoint for Emu {
     fn add_sw_breakpoint(&mut self, addr: u32) -> TargetResult<bool, Self> {
         self.breakpoints.push(addr);
@@ -0,0 +1,418 @@
+use armv4t_emu::{reg, Cpu, ExampleMem, Memory};
+use gdbstub::agent::{self, AgentError};
+use gdbstub::arch::arm::reg::ArmCoreRegs;
+use gdbstub::arch::Endianness;
+use gdbstub::target;
+use gdbstub::target::ext::tracepoints::{
+    ExperimentState, ExperimentStatus, FrameRequest, NewTracepoint, Tracepoint, TracepointAction,
+};
+use gdbstub::target::TargetResult;
+
+use crate::gdb::Emu;
+
+/// Arbitrary limit on the number of trace frames, to demonstrate reporting a
+/// full trace buffer.
+const MAX_FRAMES: usize = 4096;
+
+enum Action {
+    /// The emulator always collects the entire register file
+    Registers,
+    Memory {
+        basereg: Option<u64>,
+        offset: u64,
+        length: u64,
+    },
+    Expression(Vec<u8>),
+}
+
+struct TracepointInfo {
+    number: Tracepoint,
+    addr: u32,
+    enabled: bool,
+    step_count: u64,
+    pass_count: u64,
+    hits: u64,
+    condition: Option<Vec<u8>>,
+    actions: Vec<Action>,
+    step_actions: Vec<Action>,
+}
+
+pub struct TraceFrame {
+    number: Tracepoint,
+    pc: u32,
+    regs: Option<ArmCoreRegs>,
+    mem: Vec<(u32, Vec<u8>)>,
+}
+
+impl TraceFrame {
+    pub fn read_registers(&self, regs: &mut ArmCoreRegs) {
+        match &self.regs {
+            Some(collected) => *regs = collected.clone(),
+            // like gdbserver, fall back to reporting the tracepoint's address as the PC
+            None => regs.pc = self.pc,
+        }
+    }
+
+    /// `reg` is an internal register number of `armv4t_emu`.
+    pub fn read_register(&self, reg: u8) -> Option<u32> {
+        let regs = match &self.regs {
+            Some(regs) => regs,
+            None if reg == reg::PC => return Some(self.pc),
+            None => return None,
+        };
+
+        match reg {
+            0..=12 => Some(regs.r[reg as usize]),
+            reg::SP => Some(regs.sp),
+            reg::LR => Some(regs.lr),
+            reg::PC => Some(regs.pc),
+            reg::CPSR => Some(regs.cpsr),
+            _ => None,
+        }
+    }
+
+    /// Returns `false` if any part of the address range wasn't collected.
+    pub fn read_addrs(&self, start_addr: u32, data: &mut [u8]) -> bool {
+        for (addr, val) in (start_addr..).zip(data.iter_mut()) {
+            let byte = self
+                .mem
+                .iter()
+                .find_map(|(base, bytes)| bytes.get(addr.wrapping_sub(*base) as usize).copied());
+            match byte {
+                Some(byte) => *val = byte,
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+pub struct TraceState {
+    state: ExperimentState,
+    tracepoints: Vec<TracepointInfo>,
+    frames: Vec<TraceFrame>,
+    /// (tracepoint index, remaining steps) of any ongoing "while-stepping"
+    /// collection.
+    stepping: Option<(usize, u64)>,
+    selected: Option<usize>,
+}
+
+impl TraceState {
+    pub fn new() -> TraceState {
+        TraceState {
+            state: ExperimentState::NotRun,
+            tracepoints: Vec::new(),
+            frames: Vec::new(),
+            stepping: None,
+            selected: None,
+        }
+    }
+
+    pub fn selected_frame(&self) -> Option<&TraceFrame> {
+        self.selected.map(|i| &self.frames[i])
+    }
+}
+
+/// Agent expression context used to evaluate tracepoint conditions and collect
+/// data into trace frames.
+struct Collector<'a> {
+    cpu: &'a Cpu,
+    mem: &'a mut ExampleMem,
+    frame: Option<&'a mut TraceFrame>,
+}
+
+impl Collector<'_> {
+    fn reg(&self, regno: u64) -> Option<u32> {
+        let mode = self.cpu.mode();
+        match regno {
+            0..=15 => Some(self.cpu.reg_get(mode, regno as u8)),
+            25 => Some(self.cpu.reg_get(mode, reg::CPSR)),
+            _ => None,
+        }
+    }
+
+    fn collect_regs(&mut self) {
+        let mut regs = ArmCoreRegs::default();
+        for i in 0..13 {
+            regs.r[i] = self.reg(i as u64).unwrap();
+        }
+        regs.sp = self.reg(13).unwrap();
+        regs.lr = self.reg(14).unwrap();
+        regs.pc = self.reg(15).unwrap();
+        regs.cpsr = self.reg(25).unwrap();
+
+        if let Some(frame) = &mut self.frame {
+            frame.regs = Some(regs);
+        }
+    }
+
+    fn collect_mem(&mut self, addr: u32, len: usize) {
+        let mem = &mut self.mem;
+        let bytes = (addr..).take(len).map(|a| mem.r8(a)).collect();
+        if let Some(frame) = &mut self.frame {
+            frame.mem.push((addr, bytes));
+        }
+    }
+
+    fn perform(&mut self, action: &Action) {
+        match *action {
+            Action::Registers => self.collect_regs(),
+            Action::Memory {
+                basereg,
+                offset,
+                length,
+            } => {
+                let base = match basereg {
+                    Some(regno) => match self.reg(regno) {
+                        Some(val) => val as u64,
+                        None => return,
+                    },
+                    None => 0,
+                };
+                self.collect_mem(base.wrapping_add(offset) as u32, length as usize);
+            }
+            Action::Expression(ref expr) => {
+                // errors are ignored, keeping whatever was collected up until that point
+                let _ = agent::eval(self, expr, &mut [0; 100]);
+            }
+        }
+    }
+}
+
+impl agent::Context for Collector<'_> {
+    type Error = &'static str;
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Little
+    }
+
+    fn read_addrs(&mut self, addr: u64, data: &mut [u8]) -> Result<(), AgentError<Self::Error>> {
+        for (addr, val) in (addr as u32..).zip(data.iter_mut()) {
+            *val = self.mem.r8(addr)
+        }
+        Ok(())
+    }
+
+    fn read_register(
+        &mut self,
+        regno: u16,
+        dst: &mut [u8],
+    ) -> Result<usize, AgentError<Self::Error>> {
+        let val = self
+            .reg(regno as u64)
+            .ok_or(AgentError::InvalidRegister(regno))?;
+        dst[..4].copy_from_slice(&val.to_le_bytes());
+        Ok(4)
+    }
+
+    fn trace(&mut self, addr: u64, len: usize) -> Result<(), AgentError<Self::Error>> {
+        self.collect_mem(addr as u32, len);
+        Ok(())
+    }
+}
+
+/// Collect a new trace frame, returning `false` if the trace buffer is full.
+fn collect_frame(
+    cpu: &Cpu,
+    mem: &mut ExampleMem,
+    frames: &mut Vec<TraceFrame>,
+    number: Tracepoint,
+    actions: &[Action],
+) -> bool {
+    if frames.len() >= MAX_FRAMES {
+        return false;
+    }
+
+    let mut frame = TraceFrame {
+        number,
+        pc: cpu.reg_get(cpu.mode(), reg::PC),
+        regs: None,
+        mem: Vec::new(),
+    };
+    let mut collector = Collector {
+        cpu,
+        mem,
+        frame: Some(&mut frame),
+    };
+    for action in actions {
+        collector.perform(action);
+    }
+    frames.push(frame);
+    true
+}
+
+impl Emu {
+    /// Called after each emulated instruction, collecting trace frames whenever
+    /// a tracepoint is hit while a trace experiment is running.
+    pub(crate) fn trace_step(&mut self) {
+        let TraceState {
+            state,
+            tracepoints,
+            frames,
+            stepping,
+            ..
+        } = &mut self.tracing;
+        let (cpu, mem) = (&self.cpu, &mut self.mem);
+
+        if *state != ExperimentState::Running {
+            return;
+        }
+
+        if let Some((idx, remaining)) = *stepping {
+            let tp = &tracepoints[idx];
+            if !collect_frame(cpu, mem, frames, tp.number, &tp.step_actions) {
+                *state = ExperimentState::BufferFull;
+                return;
+            }
+            *stepping = Some((idx, remaining - 1)).filter(|(_, n)| *n != 0);
+        }
+
+        let pc = cpu.reg_get(cpu.mode(), reg::PC);
+        for (idx, tp) in tracepoints.iter_mut().enumerate() {
+            if !tp.enabled || tp.addr != pc {
+                continue;
+            }
+
+            if let Some(cond) = &tp.condition {
+                let mut collector = Collector {
+                    cpu,
+                    mem,
+                    frame: None,
+                };
+                // errors are treated as a false condition
+                match agent::eval(&mut collector, cond, &mut [0; 100]) {
+                    Ok(val) if val != 0 => {}
+                    _ => continue,
+                }
+            }
+
+            tp.hits += 1;
+            if !collect_frame(cpu, mem, frames, tp.number, &tp.actions) {
+                *state = ExperimentState::BufferFull;
+                return;
+            }
+            if tp.step_count != 0 {
+                *stepping = Some((idx, tp.step_count));
+            }
+
+            if tp.pass_count != 0 && tp.hits >= tp.pass_count {
+                *state = ExperimentState::PassCount(tp.number);
+                return;
+            }
+        }
+    }
+}
+
+impl target::ext::tracepoints::Tracepoints for Emu {
+    fn tracepoints_init(&mut self) -> TargetResult<(), Self> {
+        self.tracing = TraceState::new();
+        Ok(())
+    }
+
+    fn tracepoint_create(&mut self, tp: NewTracepoint<'_, u32>) -> TargetResult<(), Self> {
+        self.tracing.tracepoints.push(TracepointInfo {
+            number: tp.number,
+            addr: tp.addr,
+            enabled: tp.enabled,
+            step_count: tp.step_count,
+            pass_count: tp.pass_count,
+            hits: 0,
+            condition: tp.condition.map(|c| c.to_vec()),
+            actions: Vec::new(),
+            step_actions: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn tracepoint_add_action(
+        &mut self,
+        tp: Tracepoint,
+        addr: u32,
+        while_stepping: bool,
+        action: TracepointAction<'_>,
+    ) -> TargetResult<(), Self> {
+        let tp = self
+            .tracing
+            .tracepoints
+            .iter_mut()
+            .find(|t| t.number == tp && t.addr == addr)
+            .ok_or(())?;
+
+        let action = match action {
+            TracepointAction::Registers { .. } => Action::Registers,
+            TracepointAction::Memory {
+                basereg,
+                offset,
+                length,
+            } => Action::Memory {
+                basereg,
+                offset,
+                length,
+            },
+            TracepointAction::Expression { expr } => Action::Expression(expr.to_vec()),
+        };
+
+        if while_stepping {
+            tp.step_actions.push(action);
+        } else {
+            tp.actions.push(action);
+        }
+        Ok(())
+    }
+
+    fn trace_experiment_start(&mut self) -> TargetResult<(), Self> {
+        let tracing = &mut self.tracing;
+        tracing.state = ExperimentState::Running;
+        tracing.frames.clear();
+        tracing.stepping = None;
+        tracing.selected = None;
+        for tp in tracing.tracepoints.iter_mut() {
+            tp.hits = 0;
+        }
+        Ok(())
+    }
+
+    fn trace_experiment_stop(&mut self) -> TargetResult<(), Self> {
+        if self.tracing.state == ExperimentState::Running {
+            self.tracing.state = ExperimentState::Stopped;
+        }
+        Ok(())
+    }
+
+    fn trace_experiment_status(&mut self) -> TargetResult<ExperimentStatus, Self> {
+        Ok(ExperimentStatus {
+            state: self.tracing.state,
+            frames: self.tracing.frames.len() as u64,
+            created: self.tracing.frames.len() as u64,
+        })
+    }
+
+    fn select_frame(
+        &mut self,
+        frame: FrameRequest<u32>,
+    ) -> TargetResult<Option<(u64, Tracepoint)>, Self> {
+        let tracing = &mut self.tracing;
+
+        let next = tracing.selected.map(|i| i + 1).unwrap_or(0);
+        let found = match frame {
+            FrameRequest::Exit => None,
+            FrameRequest::Number(n) => Some(n as usize).filter(|n| *n < tracing.frames.len()),
+            _ => tracing
+                .frames
+                .iter()
+                .enumerate()
+                .skip(next)
+                .find(|(_, f)| match frame {
+                    FrameRequest::AtPc(pc) => f.pc == pc,
+                    FrameRequest::Hit(tp) => f.number == tp,
+                    FrameRequest::Between(start, end) => (start..=end).contains(&f.pc),
+                    FrameRequest::Outside(start, end) => !(start..=end).contains(&f.pc),
+                    _ => unreachable!(),
+                })
+                .map(|(i, _)| i),
+        };
+
+        tracing.selected = found;
+        Ok(found.map(|i| (i as u64, tracing.frames[i].number)))
+    }
+}
@@ -18,7 +18,7 @@ use crate::DynResult;
 
 const HLE_RETURN_ADDR: u32 = 0x12345678;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CpuId {
     Cpu,
     Cop,
@@ -32,6 +32,15 @@ pub enum Event {
     WatchRead(u32),
 }
 
+/// Per-core execution state (only used when debugging in non-stop mode)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RunState {
+    Stopped,
+    Running,
+    Stepping,
+    StopRequested,
+}
+
 /// incredibly barebones armv4t-based emulator
 pub struct Emu {
     pub(crate) cpu: Cpu,
@@ -42,6 +51,7 @@ pub struct Emu {
     /// (read, write)
     pub(crate) watchpoint_kind: HashMap<u32, (bool, bool)>,
     pub(crate) breakpoints: Vec<u32>,
+    pub(crate) run_state: HashMap<CpuId, RunState>,
 
     // GDB seems to get gets very confused if two threads are executing the exact same code at the
     // exact same time. Maybe this is a bug with `gdbstub`?
@@ -91,6 +101,10 @@ impl Emu {
             watchpoints: Vec::new(),
             watchpoint_kind: HashMap::new(),
             breakpoints: Vec::new(),
+            run_state: [CpuId::Cpu, CpuId::Cop]
+                .iter()
+                .map(|id| (*id, RunState::Stopped))
+                .collect(),
             stall_cop_cycles: 24,
         })
     }
@@ -4,12 +4,13 @@ use gdbstub::arch;
 use gdbstub::common::Tid;
 use gdbstub::target;
 use gdbstub::target::ext::base::multithread::{
-    Actions, MultiThreadOps, ResumeAction, ThreadStopReason,
+    Actions, MultiThreadNonStop, MultiThreadOps, ResumeAction, ThreadExtraInfo, ThreadInfo,
+    ThreadStopReason, ThreadsInfo, TidSelector,
 };
 use gdbstub::target::ext::breakpoints::WatchKind;
 use gdbstub::target::{Target, TargetError, TargetResult};
 
-use crate::emu::{CpuId, Emu, Event};
+use crate::emu::{CpuId, Emu, Event, RunState};
 
 fn event_to_stopreason(e: Event, id: CpuId) -> ThreadStopReason<u32> {
     let tid = cpuid_to_tid(id);
@@ -64,7 +65,7 @@ impl Target for Emu {
 impl MultiThreadOps for Emu {
     fn resume(
         &mut self,
-        actions: Actions,
+        actions: Actions<u32>,
         check_gdb_interrupt: &mut dyn FnMut() -> bool,
     ) -> Result<ThreadStopReason<u32>, Self::Error> {
         // in this emulator, each core runs in lock-step, so we can ignore the
@@ -80,12 +81,14 @@ impl MultiThreadOps for Emu {
         let actions = actions.collect::<Vec<_>>();
         let (_, action) = actions[0];
 
+        // the emulated system doesn't have a notion of signals, so resuming with a
+        // signal is treated the same as a plain step/continue.
         match action {
-            ResumeAction::Step => match self.step() {
+            ResumeAction::Step | ResumeAction::StepWithSignal(_) => match self.step() {
                 Some((event, id)) => Ok(event_to_stopreason(event, id)),
                 None => Ok(ThreadStopReason::DoneStep),
             },
-            ResumeAction::Continue => {
+            ResumeAction::Continue | ResumeAction::ContinueWithSignal(_) => {
                 let mut cycles: usize = 0;
                 loop {
                     // check for GDB interrupt every 1024 instructions
@@ -99,6 +102,9 @@ impl MultiThreadOps for Emu {
                     };
                 }
             }
+            // any other actions (e.g: `Stop`, which is only sent when running in non-stop mode)
+            // are only sent to targets which explicitly opt-in to them
+            _ => Err("unexpected resume action"),
         }
     }
 
@@ -180,6 +186,142 @@ impl MultiThreadOps for Emu {
         register_thread(cpuid_to_tid(CpuId::Cop));
         Ok(())
     }
+
+    fn support_non_stop(
+        &mut self,
+    ) -> Option<target::ext::base::multithread::MultiThreadNonStopOps<Self>> {
+        Some(self)
+    }
+
+    fn support_threads_info(
+        &mut self,
+    ) -> Option<target::ext::base::multithread::ThreadsInfoOps<Self>> {
+        Some(self)
+    }
+
+    fn support_thread_extra_info(
+        &mut self,
+    ) -> Option<target::ext::base::multithread::ThreadExtraInfoOps<Self>> {
+        Some(self)
+    }
+}
+
+impl Emu {
+    fn run_state_description(&self, id: CpuId) -> &'static [u8] {
+        match self.run_state[&id] {
+            RunState::Stopped => b"Stopped",
+            RunState::Running => b"Running",
+            RunState::Stepping => b"Stepping",
+            RunState::StopRequested => b"Stopping",
+        }
+    }
+}
+
+impl ThreadsInfo for Emu {
+    fn list_threads_info(
+        &mut self,
+        thread_info: &mut dyn FnMut(Tid, ThreadInfo<'_>),
+    ) -> Result<(), Self::Error> {
+        for &(id, name, core) in &[(CpuId::Cpu, b"cpu", 0), (CpuId::Cop, b"cop", 1)] {
+            thread_info(
+                cpuid_to_tid(id),
+                ThreadInfo {
+                    name: Some(name),
+                    core: Some(core),
+                    extra: Some(self.run_state_description(id)),
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ThreadExtraInfo for Emu {
+    fn thread_extra_info(&mut self, tid: Tid, buf: &mut [u8]) -> TargetResult<usize, Self> {
+        let id = tid_to_cpuid(tid).map_err(|_| TargetError::NonFatal)?;
+        let info = self.run_state_description(id);
+        let n = info.len().min(buf.len());
+        buf[..n].copy_from_slice(&info[..n]);
+        Ok(n)
+    }
+}
+
+impl MultiThreadNonStop for Emu {
+    fn resume_non_stop(&mut self, actions: Actions<u32>) -> Result<(), Self::Error> {
+        // unlike in all-stop mode, each core is run independently, and keeps
+        // running until GDB explicitly asks for it to be stopped.
+        let mut updated = Vec::new();
+        for (tid_selector, action) in actions {
+            let ids = match tid_selector {
+                TidSelector::WithID(tid) => vec![tid_to_cpuid(tid)?],
+                TidSelector::All => vec![CpuId::Cpu, CpuId::Cop],
+            };
+
+            for id in ids {
+                // only the first action which matches a core should be applied
+                if updated.contains(&id) {
+                    continue;
+                }
+                updated.push(id);
+
+                let state = match action {
+                    ResumeAction::Continue | ResumeAction::ContinueWithSignal(_) => {
+                        RunState::Running
+                    }
+                    ResumeAction::Step | ResumeAction::StepWithSignal(_) => RunState::Stepping,
+                    ResumeAction::Stop => RunState::StopRequested,
+                    _ => return Err("unexpected resume action"),
+                };
+                self.run_state.insert(id, state);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll_non_stop(
+        &mut self,
+        check_gdb_incoming: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<ThreadStopReason<u32>>, Self::Error> {
+        let mut cycles: usize = 0;
+        loop {
+            for id in [CpuId::Cpu, CpuId::Cop].iter().copied() {
+                let tid = cpuid_to_tid(id);
+                let stop_reason = match self.run_state[&id] {
+                    RunState::Stopped => None,
+                    RunState::StopRequested => {
+                        Some(ThreadStopReason::SignalWithThread { tid, signal: 0 })
+                    }
+                    RunState::Stepping => match self.step_core(id) {
+                        Some(event) => Some(event_to_stopreason(event, id)),
+                        None => Some(ThreadStopReason::SignalWithThread { tid, signal: 5 }),
+                    },
+                    RunState::Running => self
+                        .step_core(id)
+                        .map(|event| event_to_stopreason(event, id)),
+                };
+
+                if let Some(stop_reason) = stop_reason {
+                    self.run_state.insert(id, RunState::Stopped);
+                    return Ok(Some(stop_reason));
+                }
+            }
+
+            if self.run_state.values().all(|s| *s == RunState::Stopped) {
+                return Ok(None);
+            }
+
+            // check for incoming GDB packets every 1024 instructions
+            cycles += 1;
+            if cycles % 1024 == 0 && check_gdb_incoming() {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn is_thread_stopped(&mut self, tid: Tid) -> Result<bool, Self::Error> {
+        Ok(self.run_state[&tid_to_cpuid(tid)?] == RunState::Stopped)
+    }
 }
 
 impl target::ext::breakpoints::SwBreakpoint for Emu {
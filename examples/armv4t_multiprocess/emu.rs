@@ -0,0 +1,101 @@
+use armv4t_emu::{reg, Cpu, ExampleMem, Memory, Mode};
+
+use crate::DynResult;
+
+const HLE_RETURN_ADDR: u32 = 0x12345678;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    Exited(u8),
+    Break,
+}
+
+/// A single emulated process, with its own CPU and address space.
+pub struct Process {
+    pub cpu: Cpu,
+    pub mem: ExampleMem,
+}
+
+impl Process {
+    fn new(program_elf: &[u8]) -> DynResult<Process> {
+        let mut cpu = Cpu::new();
+        let mut mem = ExampleMem::new();
+
+        // load ELF
+        let elf_header = goblin::elf::Elf::parse(program_elf)?;
+
+        // copy all in-memory sections from the ELF file into system RAM
+        let sections = elf_header
+            .section_headers
+            .iter()
+            .filter(|h| h.is_alloc() && h.sh_type != goblin::elf::section_header::SHT_NOBITS);
+
+        for h in sections {
+            for (i, b) in program_elf[h.file_range()].iter().enumerate() {
+                mem.w8(h.sh_addr as u32 + i as u32, *b);
+            }
+        }
+
+        // setup execution state
+        cpu.reg_set(Mode::User, reg::SP, 0x10000000);
+        cpu.reg_set(Mode::User, reg::LR, HLE_RETURN_ADDR);
+        cpu.reg_set(Mode::User, reg::PC, elf_header.entry as u32);
+        cpu.reg_set(Mode::User, reg::CPSR, 0x10); // user mode
+
+        Ok(Process { cpu, mem })
+    }
+}
+
+/// incredibly barebones armv4t-based "operating system", which runs several
+/// independent copies of the same program.
+pub struct Emu {
+    /// Live processes, indexed by `pid - 1` (`None` once a process has exited
+    /// or was killed).
+    pub(crate) procs: Vec<Option<Process>>,
+
+    pub(crate) breakpoints: Vec<u32>,
+}
+
+impl Emu {
+    pub fn new(program_elf: &[u8], num_procs: usize) -> DynResult<Emu> {
+        let mut procs = Vec::new();
+        for _ in 0..num_procs {
+            procs.push(Some(Process::new(program_elf)?));
+        }
+
+        Ok(Emu {
+            procs,
+            breakpoints: Vec::new(),
+        })
+    }
+
+    /// Execute a single instruction in process `idx`.
+    pub fn step(&mut self, idx: usize) -> Option<Event> {
+        let proc = self.procs[idx].as_mut()?;
+        proc.cpu.step(&mut proc.mem);
+        let pc = proc.cpu.reg_get(Mode::User, reg::PC);
+
+        if pc == HLE_RETURN_ADDR {
+            let status = proc.cpu.reg_get(Mode::User, 0) as u8;
+            self.procs[idx] = None;
+            return Some(Event::Exited(status));
+        }
+
+        if self.breakpoints.contains(&pc) {
+            return Some(Event::Break);
+        }
+
+        None
+    }
+
+    /// Execute a single instruction in every live process, stopping at the
+    /// first event which occurs (alongside the process it occurred in).
+    pub fn step_all(&mut self) -> Option<(Event, usize)> {
+        for idx in 0..self.procs.len() {
+            if let Some(event) = self.step(idx) {
+                return Some((event, idx));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,222 @@
+use armv4t_emu::{reg, Memory};
+
+use gdbstub::arch;
+use gdbstub::common::{Pid, Tid};
+use gdbstub::target;
+use gdbstub::target::ext::base::multiprocess::{
+    Actions, MultiProcessOps, ProcessStopReason, ResumeAction, ThreadSelector,
+};
+use gdbstub::target::{Target, TargetError, TargetResult};
+
+use crate::emu::{Emu, Event, Process};
+
+// each process only ever contains a single thread
+fn single_tid() -> Tid {
+    Tid::new(1).unwrap()
+}
+
+fn idx_to_pid(idx: usize) -> Pid {
+    Pid::new(idx + 1).unwrap()
+}
+
+impl Emu {
+    fn process(&mut self, pid: Pid, tid: Tid) -> Result<&mut Process, TargetError<&'static str>> {
+        if tid != single_tid() {
+            return Err(TargetError::NonFatal);
+        }
+
+        self.procs
+            .get_mut(pid.get() - 1)
+            .and_then(Option::as_mut)
+            .ok_or(TargetError::NonFatal)
+    }
+
+    fn event_to_stopreason(&self, event: Event, idx: usize) -> ProcessStopReason<u32> {
+        let pid = idx_to_pid(idx);
+        match event {
+            Event::Exited(_) if self.procs.iter().all(Option::is_none) => ProcessStopReason::Halted,
+            Event::Exited(status) => ProcessStopReason::Exited { pid, status },
+            Event::Break => ProcessStopReason::SwBreak {
+                pid,
+                tid: single_tid(),
+            },
+        }
+    }
+}
+
+impl Target for Emu {
+    type Arch = arch::arm::Armv4t;
+    type Error = &'static str;
+
+    fn base_ops(&mut self) -> target::ext::base::BaseOps<Self::Arch, Self::Error> {
+        target::ext::base::BaseOps::MultiProcess(self)
+    }
+
+    fn sw_breakpoint(&mut self) -> Option<target::ext::breakpoints::SwBreakpointOps<Self>> {
+        Some(self)
+    }
+}
+
+impl MultiProcessOps for Emu {
+    fn resume(
+        &mut self,
+        mut actions: Actions<u32>,
+        check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<ProcessStopReason<u32>, Self::Error> {
+        // every process runs in lock-step, so only the first action is used to
+        // determine whether to step or continue.
+        let (selector, action) = actions.next().ok_or("missing resume action")?;
+
+        // the emulated system doesn't have a notion of signals, so resuming with a
+        // signal is treated the same as a plain step/continue.
+        match action {
+            ResumeAction::Step | ResumeAction::StepWithSignal(_) => {
+                let pid = match selector {
+                    ThreadSelector::WithID { pid, .. } | ThreadSelector::AllInProcess(pid) => pid,
+                    ThreadSelector::All => {
+                        let idx = self.procs.iter().position(Option::is_some);
+                        idx_to_pid(idx.ok_or("no live processes")?)
+                    }
+                };
+
+                let idx = pid.get() - 1;
+                Ok(match self.step(idx) {
+                    Some(event) => self.event_to_stopreason(event, idx),
+                    None => ProcessStopReason::SignalWithThread {
+                        pid,
+                        tid: single_tid(),
+                        signal: 5,
+                    },
+                })
+            }
+            ResumeAction::Continue | ResumeAction::ContinueWithSignal(_) => {
+                let mut cycles: usize = 0;
+                loop {
+                    // check for GDB interrupt every 1024 instructions
+                    if cycles % 1024 == 0 && check_gdb_interrupt() {
+                        return Ok(ProcessStopReason::GdbInterrupt);
+                    }
+                    cycles += 1;
+
+                    if let Some((event, idx)) = self.step_all() {
+                        return Ok(self.event_to_stopreason(event, idx));
+                    };
+                }
+            }
+            // any other actions are only sent to targets which explicitly opt-in to them
+            _ => Err("unexpected resume action"),
+        }
+    }
+
+    fn read_registers(
+        &mut self,
+        regs: &mut arch::arm::reg::ArmCoreRegs,
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let cpu = &mut self.process(pid, tid)?.cpu;
+        let mode = cpu.mode();
+
+        for i in 0..13 {
+            regs.r[i] = cpu.reg_get(mode, i as u8);
+        }
+        regs.sp = cpu.reg_get(mode, reg::SP);
+        regs.lr = cpu.reg_get(mode, reg::LR);
+        regs.pc = cpu.reg_get(mode, reg::PC);
+        regs.cpsr = cpu.reg_get(mode, reg::CPSR);
+
+        Ok(())
+    }
+
+    fn write_registers(
+        &mut self,
+        regs: &arch::arm::reg::ArmCoreRegs,
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let cpu = &mut self.process(pid, tid)?.cpu;
+        let mode = cpu.mode();
+
+        for i in 0..13 {
+            cpu.reg_set(mode, i, regs.r[i as usize]);
+        }
+        cpu.reg_set(mode, reg::SP, regs.sp);
+        cpu.reg_set(mode, reg::LR, regs.lr);
+        cpu.reg_set(mode, reg::PC, regs.pc);
+        cpu.reg_set(mode, reg::CPSR, regs.cpsr);
+
+        Ok(())
+    }
+
+    fn read_addrs(
+        &mut self,
+        start_addr: u32,
+        data: &mut [u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let mem = &mut self.process(pid, tid)?.mem;
+        for (addr, val) in (start_addr..).zip(data.iter_mut()) {
+            *val = mem.r8(addr)
+        }
+        Ok(())
+    }
+
+    fn write_addrs(
+        &mut self,
+        start_addr: u32,
+        data: &[u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let mem = &mut self.process(pid, tid)?.mem;
+        for (addr, val) in (start_addr..).zip(data.iter().copied()) {
+            mem.w8(addr, val)
+        }
+        Ok(())
+    }
+
+    fn list_active_threads(
+        &mut self,
+        register_thread: &mut dyn FnMut(Pid, Tid),
+    ) -> Result<(), Self::Error> {
+        for (idx, proc) in self.procs.iter().enumerate() {
+            if proc.is_some() {
+                register_thread(idx_to_pid(idx), single_tid());
+            }
+        }
+        Ok(())
+    }
+
+    fn kill(&mut self, pid: Pid) -> TargetResult<(), Self> {
+        eprintln!("GDB sent a kill request for pid {:?}", pid);
+        match self.procs.get_mut(pid.get() - 1) {
+            Some(proc @ Some(_)) => *proc = None,
+            _ => return Err(TargetError::NonFatal),
+        }
+        Ok(())
+    }
+
+    fn detach(&mut self, pid: Pid) -> TargetResult<(), Self> {
+        eprintln!("GDB detached from pid {:?}", pid);
+        // detached processes are simply left to run to completion once the debugging
+        // session ends, so there's nothing to do here.
+        Ok(())
+    }
+}
+
+impl target::ext::breakpoints::SwBreakpoint for Emu {
+    fn add_sw_breakpoint(&mut self, addr: u32) -> TargetResult<bool, Self> {
+        self.breakpoints.push(addr);
+        Ok(true)
+    }
+
+    fn remove_sw_breakpoint(&mut self, addr: u32) -> TargetResult<bool, Self> {
+        match self.breakpoints.iter().position(|x| *x == addr) {
+            None => return Ok(false),
+            Some(pos) => self.breakpoints.remove(pos),
+        };
+
+        Ok(true)
+    }
+}
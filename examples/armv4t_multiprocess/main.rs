@@ -0,0 +1,48 @@
+use std::net::{TcpListener, TcpStream};
+
+use gdbstub::{DisconnectReason, GdbStub};
+
+pub type DynResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+static TEST_PROGRAM_ELF: &[u8] = include_bytes!("../armv4t/test_bin/test.elf");
+
+mod emu;
+mod gdb;
+
+fn wait_for_tcp(port: u16) -> DynResult<TcpStream> {
+    let sockaddr = format!("127.0.0.1:{}", port);
+    eprintln!("Waiting for a GDB connection on {:?}...", sockaddr);
+
+    let sock = TcpListener::bind(sockaddr)?;
+    let (stream, addr) = sock.accept()?;
+    eprintln!("Debugger connected from {}", addr);
+
+    Ok(stream)
+}
+
+fn main() -> DynResult<()> {
+    pretty_env_logger::init();
+
+    let mut emu = emu::Emu::new(TEST_PROGRAM_ELF, 2)?;
+
+    let connection = wait_for_tcp(9001)?;
+
+    // hook-up debugger
+    let mut debugger = GdbStub::new(connection);
+
+    match debugger.run(&mut emu)? {
+        DisconnectReason::Disconnect => {
+            // run to completion
+            emu.breakpoints.clear();
+            while emu.procs.iter().any(Option::is_some) {
+                if let Some((emu::Event::Exited(status), idx)) = emu.step_all() {
+                    println!("Process {} exited with status {}", idx + 1, status);
+                }
+            }
+        }
+        DisconnectReason::TargetHalted => println!("Target halted!"),
+        DisconnectReason::Kill => println!("GDB sent a kill command!"),
+    }
+
+    Ok(())
+}
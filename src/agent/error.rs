@@ -0,0 +1,61 @@
+use core::fmt::{self, Debug, Display};
+
+/// An error which may occur while evaluating an agent expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AgentError<E> {
+    /// The bytecode ended in the middle of an instruction, or without reaching
+    /// an `end` opcode (e.g: due to a jump past the end of the bytecode).
+    UnexpectedEof,
+    /// Encountered an opcode which isn't part of the agent expression
+    /// language.
+    InvalidOpcode(u8),
+    /// Encountered an opcode which isn't supported by this implementation
+    /// (e.g: floating point operations, `printf`).
+    UnsupportedOpcode(u8),
+    /// Pushed a value onto a full stack.
+    StackOverflow,
+    /// Popped a value off of an empty stack.
+    StackUnderflow,
+    /// Divided (or took the remainder) by zero.
+    DivideByZero,
+    /// The specified memory could not be read.
+    MemoryAccess(u64),
+    /// The specified (raw GDB) register number isn't recognized by the target,
+    /// or could not be read.
+    InvalidRegister(u16),
+    /// The specified trace state variable isn't available.
+    InvalidTraceStateVariable(u16),
+    /// Executed more than [`MAX_STEPS`](super::MAX_STEPS) instructions without
+    /// reaching an `end` opcode (e.g: due to an infinite loop).
+    TooManySteps,
+    /// The target threw a fatal error.
+    TargetError(E),
+}
+
+impl<E> Display for AgentError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::AgentError::*;
+        match self {
+            UnexpectedEof => write!(f, "Agent expression ended unexpectedly."),
+            InvalidOpcode(op) => write!(f, "Invalid agent expression opcode: {:#04x}", op),
+            UnsupportedOpcode(op) => write!(f, "Unsupported agent expression opcode: {:#04x}", op),
+            StackOverflow => write!(f, "Agent expression stack overflow."),
+            StackUnderflow => write!(f, "Agent expression stack underflow."),
+            DivideByZero => write!(f, "Agent expression divided by zero."),
+            MemoryAccess(addr) => write!(f, "Could not read memory at {:#x}", addr),
+            InvalidRegister(regno) => write!(f, "Could not read register {}", regno),
+            InvalidTraceStateVariable(n) => {
+                write!(f, "Trace state variable {} is not available", n)
+            }
+            TooManySteps => write!(f, "Agent expression executed too many instructions."),
+            TargetError(e) => write!(f, "Target threw a fatal error: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for AgentError<E> where E: Debug {}
@@ -0,0 +1,635 @@
+//! An interpreter for GDB's
+//! [agent expression](https://sourceware.org/gdb/current/onlinedocs/gdb/Agent-Expressions.html)
+//! bytecode.
+//!
+//! GDB compiles source-level expressions (e.g: breakpoint conditions,
+//! tracepoint collection actions, etc...) into a simple stack-based bytecode,
+//! which can be evaluated by the target without having to round-trip back to
+//! GDB.
+//!
+//! `gdbstub` uses this module internally (e.g: to implement target-side
+//! breakpoint conditions), but it can also be used directly by targets which
+//! receive agent expressions via other channels.
+//!
+//! The interpreter is fully `#![no_std]` compatible, and never allocates: the
+//! evaluation stack is provided by the caller, and evaluation fails with
+//! [`AgentError::StackOverflow`] if the expression exceeds its capacity.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use gdbstub::agent::{self, TargetContext};
+//!
+//! let mut stack = [0; 100];
+//! let mut ctx = TargetContext::new(&mut target, pid, tid);
+//! let val = agent::eval(&mut ctx, bytecode, &mut stack)?;
+//! ```
+
+use crate::arch::{Arch, Endianness, RegId, Registers};
+use crate::common::{Pid, Tid};
+use crate::target::ext::base::BaseOps;
+use crate::target::{Target, TargetError};
+
+mod error;
+
+pub use error::AgentError;
+
+/// Agent expression opcodes.
+///
+/// See `gdb/gdbsupport/ax.def` in the GDB source tree.
+mod op {
+    pub const FLOAT: u8 = 0x01;
+    pub const ADD: u8 = 0x02;
+    pub const SUB: u8 = 0x03;
+    pub const MUL: u8 = 0x04;
+    pub const DIV_SIGNED: u8 = 0x05;
+    pub const DIV_UNSIGNED: u8 = 0x06;
+    pub const REM_SIGNED: u8 = 0x07;
+    pub const REM_UNSIGNED: u8 = 0x08;
+    pub const LSH: u8 = 0x09;
+    pub const RSH_SIGNED: u8 = 0x0a;
+    pub const RSH_UNSIGNED: u8 = 0x0b;
+    pub const TRACE: u8 = 0x0c;
+    pub const TRACE_QUICK: u8 = 0x0d;
+    pub const LOG_NOT: u8 = 0x0e;
+    pub const BIT_AND: u8 = 0x0f;
+    pub const BIT_OR: u8 = 0x10;
+    pub const BIT_XOR: u8 = 0x11;
+    pub const BIT_NOT: u8 = 0x12;
+    pub const EQUAL: u8 = 0x13;
+    pub const LESS_SIGNED: u8 = 0x14;
+    pub const LESS_UNSIGNED: u8 = 0x15;
+    pub const EXT: u8 = 0x16;
+    pub const REF8: u8 = 0x17;
+    pub const REF16: u8 = 0x18;
+    pub const REF32: u8 = 0x19;
+    pub const REF64: u8 = 0x1a;
+    pub const REF_FLOAT: u8 = 0x1b;
+    pub const REF_DOUBLE: u8 = 0x1c;
+    pub const REF_LONG_DOUBLE: u8 = 0x1d;
+    pub const L_TO_D: u8 = 0x1e;
+    pub const D_TO_L: u8 = 0x1f;
+    pub const IF_GOTO: u8 = 0x20;
+    pub const GOTO: u8 = 0x21;
+    pub const CONST8: u8 = 0x22;
+    pub const CONST16: u8 = 0x23;
+    pub const CONST32: u8 = 0x24;
+    pub const CONST64: u8 = 0x25;
+    pub const REG: u8 = 0x26;
+    pub const END: u8 = 0x27;
+    pub const DUP: u8 = 0x28;
+    pub const POP: u8 = 0x29;
+    pub const ZERO_EXT: u8 = 0x2a;
+    pub const SWAP: u8 = 0x2b;
+    pub const GETV: u8 = 0x2c;
+    pub const SETV: u8 = 0x2d;
+    pub const TRACEV: u8 = 0x2e;
+    pub const TRACENZ: u8 = 0x2f;
+    pub const TRACE16: u8 = 0x30;
+    pub const PICK: u8 = 0x32;
+    pub const ROT: u8 = 0x33;
+    pub const PRINTF: u8 = 0x34;
+}
+
+/// Provides an agent expression with access to target state.
+///
+/// Most targets should simply use [`TargetContext`], which reads memory and
+/// registers via the target's base operations.
+pub trait Context {
+    /// Fatal error type (see [`AgentError::TargetError`]).
+    type Error;
+
+    /// The target's byte order, used to interpret memory and register values.
+    fn endianness(&self) -> Endianness;
+
+    /// Read bytes from the specified address range.
+    fn read_addrs(&mut self, addr: u64, data: &mut [u8]) -> Result<(), AgentError<Self::Error>>;
+
+    /// Read the register with the raw GDB register number `regno` into `dst`
+    /// (using the target's native byte order), returning the size of the
+    /// register in bytes.
+    fn read_register(
+        &mut self,
+        regno: u16,
+        dst: &mut [u8],
+    ) -> Result<usize, AgentError<Self::Error>>;
+
+    /// Record the specified memory range (invoked by the `trace` family of
+    /// opcodes when collecting tracepoint data).
+    ///
+    /// Defaults to a no-op.
+    fn trace(&mut self, addr: u64, len: usize) -> Result<(), AgentError<Self::Error>> {
+        let _ = (addr, len);
+        Ok(())
+    }
+
+    /// Get the value of the trace state variable `n`.
+    fn get_tsv(&mut self, n: u16) -> Result<u64, AgentError<Self::Error>> {
+        Err(AgentError::InvalidTraceStateVariable(n))
+    }
+
+    /// Set the value of the trace state variable `n`.
+    fn set_tsv(&mut self, n: u16, val: u64) -> Result<(), AgentError<Self::Error>> {
+        let _ = val;
+        Err(AgentError::InvalidTraceStateVariable(n))
+    }
+
+    /// Record the value of the trace state variable `n` (invoked by the
+    /// `tracev` opcode when collecting tracepoint data).
+    ///
+    /// Defaults to a no-op.
+    fn trace_tsv(&mut self, n: u16) -> Result<(), AgentError<Self::Error>> {
+        let _ = n;
+        Ok(())
+    }
+}
+
+/// A [`Context`] which accesses a particular thread's memory and registers
+/// via the target's base operations (i.e: `read_addrs` and `read_registers`).
+///
+/// `pid` is ignored by single/multi threaded targets, and `tid` is ignored by
+/// single threaded targets.
+pub struct TargetContext<'a, T: Target> {
+    target: &'a mut T,
+    pid: Pid,
+    tid: Tid,
+}
+
+impl<'a, T: Target> TargetContext<'a, T> {
+    /// Create a new `TargetContext` for the specified thread.
+    pub fn new(target: &'a mut T, pid: Pid, tid: Tid) -> TargetContext<'a, T> {
+        TargetContext { target, pid, tid }
+    }
+}
+
+impl<T: Target> Context for TargetContext<'_, T> {
+    type Error = T::Error;
+
+    fn endianness(&self) -> Endianness {
+        T::Arch::endianness()
+    }
+
+    fn read_addrs(&mut self, addr: u64, data: &mut [u8]) -> Result<(), AgentError<T::Error>> {
+        use num_traits::NumCast;
+
+        let start_addr: <T::Arch as Arch>::Usize =
+            NumCast::from(addr).ok_or(AgentError::MemoryAccess(addr))?;
+        let (pid, tid) = (self.pid, self.tid);
+        let res = match self.target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.read_addrs(start_addr, data),
+            BaseOps::MultiThread(ops) => ops.read_addrs(start_addr, data, tid),
+            BaseOps::MultiProcess(ops) => ops.read_addrs(start_addr, data, pid, tid),
+        };
+        handle_error(res, AgentError::MemoryAccess(addr))
+    }
+
+    fn read_register(&mut self, regno: u16, dst: &mut [u8]) -> Result<usize, AgentError<T::Error>> {
+        // Not every target implements single-register reads (the base API's default
+        // `read_register` implementation is a no-op), so the register is extracted from the
+        // serialized register file instead. This requires knowing the size of every preceding
+        // register.
+        let mut offset = 0;
+        for id in 0..regno as usize {
+            let (_, size) = <T::Arch as Arch>::RegId::from_raw_id(id)
+                .ok_or(AgentError::InvalidRegister(regno))?;
+            offset += size;
+        }
+        let (_, size) = <T::Arch as Arch>::RegId::from_raw_id(regno as usize)
+            .ok_or(AgentError::InvalidRegister(regno))?;
+        let dst = dst
+            .get_mut(..size)
+            .ok_or(AgentError::InvalidRegister(regno))?;
+
+        let mut regs: <T::Arch as Arch>::Registers = Default::default();
+        let (pid, tid) = (self.pid, self.tid);
+        let res = match self.target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.read_registers(&mut regs),
+            BaseOps::MultiThread(ops) => ops.read_registers(&mut regs, tid),
+            BaseOps::MultiProcess(ops) => ops.read_registers(&mut regs, pid, tid),
+        };
+        handle_error(res, AgentError::InvalidRegister(regno))?;
+
+        let mut idx: usize = 0;
+        let mut found = 0;
+        regs.gdb_serialize(|b| {
+            let slot = idx.checked_sub(offset).and_then(|i| dst.get_mut(i));
+            if let (Some(b), Some(slot)) = (b, slot) {
+                *slot = b;
+                found += 1;
+            }
+            idx += 1;
+        });
+
+        // missing (i.e: uncollected) registers can't be used in an expression
+        if found != size {
+            return Err(AgentError::InvalidRegister(regno));
+        }
+        Ok(size)
+    }
+}
+
+/// Convert a `TargetResult` into an `AgentError`, using `non_fatal` for any
+/// non-fatal errors.
+fn handle_error<V, E>(
+    res: Result<V, TargetError<E>>,
+    non_fatal: AgentError<E>,
+) -> Result<V, AgentError<E>> {
+    match res {
+        Ok(v) => Ok(v),
+        Err(TargetError::Fatal(e)) => Err(AgentError::TargetError(e)),
+        Err(_) => Err(non_fatal),
+    }
+}
+
+/// A fixed-capacity evaluation stack, backed by a caller-provided buffer.
+struct Stack<'a, E> {
+    vals: &'a mut [u64],
+    len: usize,
+    _error: core::marker::PhantomData<E>,
+}
+
+impl<E> Stack<'_, E> {
+    fn push(&mut self, val: u64) -> Result<(), AgentError<E>> {
+        *self
+            .vals
+            .get_mut(self.len)
+            .ok_or(AgentError::StackOverflow)? = val;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<u64, AgentError<E>> {
+        self.len = self.len.checked_sub(1).ok_or(AgentError::StackUnderflow)?;
+        Ok(self.vals[self.len])
+    }
+
+    fn peek(&self, depth: usize) -> Result<u64, AgentError<E>> {
+        let idx = self
+            .len
+            .checked_sub(depth + 1)
+            .ok_or(AgentError::StackUnderflow)?;
+        Ok(self.vals[idx])
+    }
+}
+
+/// Interpret up to 8 bytes of data (with the given byte order) as an integer.
+fn from_bytes(buf: &[u8], endianness: Endianness) -> u64 {
+    let mut val = 0;
+    match endianness {
+        Endianness::Little => {
+            for b in buf.iter().take(8).rev() {
+                val = (val << 8) | *b as u64;
+            }
+        }
+        Endianness::Big => {
+            for b in buf.iter().skip(buf.len().saturating_sub(8)) {
+                val = (val << 8) | *b as u64;
+            }
+        }
+    }
+    val
+}
+
+/// Sign-extend the low `bits` bits of `val`.
+fn sign_extend(val: u64, bits: u8) -> u64 {
+    match bits {
+        0 | 64..=u8::MAX => val,
+        _ => {
+            let shift = 64 - bits as u32;
+            (((val << shift) as i64) >> shift) as u64
+        }
+    }
+}
+
+/// Zero-extend the low `bits` bits of `val`.
+fn zero_extend(val: u64, bits: u8) -> u64 {
+    match bits {
+        64..=u8::MAX => val,
+        _ => val & ((1 << bits) - 1),
+    }
+}
+
+/// The maximum number of instructions [`eval`] executes before giving up on
+/// an expression (e.g: one which jumps backwards into an infinite loop).
+pub const MAX_STEPS: usize = 10_000;
+
+/// Evaluate the agent expression `bytecode`, returning the value on top of
+/// the stack once the `end` opcode is reached.
+///
+/// `stack` is used as the expression's evaluation stack, and bounds the
+/// maximum stack depth of the expression. For reference, `gdbserver` uses a
+/// stack of 100 values.
+///
+/// Evaluation fails with [`AgentError::TooManySteps`] if the expression
+/// doesn't reach an `end` opcode within [`MAX_STEPS`] instructions.
+pub fn eval<C: Context>(
+    ctx: &mut C,
+    bytecode: &[u8],
+    stack: &mut [u64],
+) -> Result<u64, AgentError<C::Error>> {
+    let endianness = ctx.endianness();
+    let mut stack = Stack {
+        vals: stack,
+        len: 0,
+        _error: core::marker::PhantomData,
+    };
+    let mut pc = 0;
+    let mut steps = 0;
+
+    // operands are always encoded as big-endian integers
+    macro_rules! operand {
+        ($n:literal) => {{
+            let bytes = bytecode.get(pc..pc + $n).ok_or(AgentError::UnexpectedEof)?;
+            pc += $n;
+            from_bytes(bytes, Endianness::Big)
+        }};
+    }
+
+    macro_rules! binop {
+        (|$a:ident, $b:ident| $expr:expr) => {{
+            let $b = stack.pop()?;
+            let $a = stack.pop()?;
+            stack.push($expr)?;
+        }};
+    }
+
+    loop {
+        steps += 1;
+        if steps > MAX_STEPS {
+            return Err(AgentError::TooManySteps);
+        }
+
+        let opcode = *bytecode.get(pc).ok_or(AgentError::UnexpectedEof)?;
+        pc += 1;
+
+        match opcode {
+            op::ADD => binop!(|a, b| a.wrapping_add(b)),
+            op::SUB => binop!(|a, b| a.wrapping_sub(b)),
+            op::MUL => binop!(|a, b| a.wrapping_mul(b)),
+            op::DIV_SIGNED => binop!(|a, b| {
+                if b == 0 {
+                    return Err(AgentError::DivideByZero);
+                }
+                (a as i64).wrapping_div(b as i64) as u64
+            }),
+            op::DIV_UNSIGNED => binop!(|a, b| a.checked_div(b).ok_or(AgentError::DivideByZero)?),
+            op::REM_SIGNED => binop!(|a, b| {
+                if b == 0 {
+                    return Err(AgentError::DivideByZero);
+                }
+                (a as i64).wrapping_rem(b as i64) as u64
+            }),
+            op::REM_UNSIGNED => binop!(|a, b| a.checked_rem(b).ok_or(AgentError::DivideByZero)?),
+            op::LSH => binop!(|a, b| if b < 64 { a << b } else { 0 }),
+            op::RSH_SIGNED => binop!(|a, b| ((a as i64) >> b.min(63)) as u64),
+            op::RSH_UNSIGNED => binop!(|a, b| if b < 64 { a >> b } else { 0 }),
+            op::LOG_NOT => {
+                let a = stack.pop()?;
+                stack.push((a == 0) as u64)?;
+            }
+            op::BIT_AND => binop!(|a, b| a & b),
+            op::BIT_OR => binop!(|a, b| a | b),
+            op::BIT_XOR => binop!(|a, b| a ^ b),
+            op::BIT_NOT => {
+                let a = stack.pop()?;
+                stack.push(!a)?;
+            }
+            op::EQUAL => binop!(|a, b| (a == b) as u64),
+            op::LESS_SIGNED => binop!(|a, b| ((a as i64) < (b as i64)) as u64),
+            op::LESS_UNSIGNED => binop!(|a, b| (a < b) as u64),
+            op::EXT => {
+                let bits = operand!(1) as u8;
+                let a = stack.pop()?;
+                stack.push(sign_extend(a, bits))?;
+            }
+            op::ZERO_EXT => {
+                let bits = operand!(1) as u8;
+                let a = stack.pop()?;
+                stack.push(zero_extend(a, bits))?;
+            }
+            op::REF8 | op::REF16 | op::REF32 | op::REF64 => {
+                let size = 1 << (opcode - op::REF8);
+                let mut buf = [0; 8];
+                let buf = &mut buf[..size];
+                let addr = stack.pop()?;
+                ctx.read_addrs(addr, buf)?;
+                stack.push(from_bytes(buf, endianness))?;
+            }
+            op::IF_GOTO => {
+                let target = operand!(2) as usize;
+                if stack.pop()? != 0 {
+                    pc = target;
+                }
+            }
+            op::GOTO => {
+                let target = bytecode.get(pc..pc + 2).ok_or(AgentError::UnexpectedEof)?;
+                pc = from_bytes(target, Endianness::Big) as usize;
+            }
+            op::CONST8 => stack.push(operand!(1))?,
+            op::CONST16 => stack.push(operand!(2))?,
+            op::CONST32 => stack.push(operand!(4))?,
+            op::CONST64 => stack.push(operand!(8))?,
+            op::REG => {
+                let regno = operand!(2) as u16;
+                let mut buf = [0; 32]; // enough for 256-bit registers
+                let size = ctx.read_register(regno, &mut buf)?;
+                let buf = buf.get(..size).ok_or(AgentError::InvalidRegister(regno))?;
+                stack.push(from_bytes(buf, endianness))?;
+            }
+            op::END => return stack.pop(),
+            op::DUP => stack.push(stack.peek(0)?)?,
+            op::POP => {
+                stack.pop()?;
+            }
+            op::SWAP => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                stack.push(b)?;
+                stack.push(a)?;
+            }
+            op::PICK => {
+                let depth = operand!(1) as usize;
+                stack.push(stack.peek(depth)?)?;
+            }
+            op::ROT => {
+                let c = stack.pop()?;
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                stack.push(c)?;
+                stack.push(a)?;
+                stack.push(b)?;
+            }
+            op::TRACE => {
+                let len = stack.pop()?;
+                let addr = stack.pop()?;
+                ctx.trace(addr, len as usize)?;
+            }
+            op::TRACE_QUICK => {
+                let len = operand!(1) as usize;
+                ctx.trace(stack.peek(0)?, len)?;
+            }
+            op::TRACE16 => {
+                let len = operand!(2) as usize;
+                ctx.trace(stack.peek(0)?, len)?;
+            }
+            op::TRACENZ => {
+                let max_len = stack.pop()?;
+                let addr = stack.pop()?;
+                // record everything up to (and including) the first NUL byte
+                let mut len = 0;
+                while len < max_len {
+                    let mut b = [0];
+                    ctx.read_addrs(addr.wrapping_add(len), &mut b)?;
+                    len += 1;
+                    if b[0] == 0 {
+                        break;
+                    }
+                }
+                ctx.trace(addr, len as usize)?;
+            }
+            op::GETV => {
+                let n = operand!(2) as u16;
+                stack.push(ctx.get_tsv(n)?)?;
+            }
+            op::SETV => {
+                let n = operand!(2) as u16;
+                ctx.set_tsv(n, stack.peek(0)?)?;
+            }
+            op::TRACEV => {
+                let n = operand!(2) as u16;
+                ctx.trace_tsv(n)?;
+            }
+            op::FLOAT
+            | op::REF_FLOAT
+            | op::REF_DOUBLE
+            | op::REF_LONG_DOUBLE
+            | op::L_TO_D
+            | op::D_TO_L
+            | op::PRINTF => return Err(AgentError::UnsupportedOpcode(opcode)),
+            _ => return Err(AgentError::InvalidOpcode(opcode)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Little-endian test context with 4 registers (`r<n> == n * 0x11`), and
+    /// 256 bytes of memory (`mem[addr] == addr`).
+    struct MockContext {
+        traced: Vec<(u64, usize)>,
+    }
+
+    impl Context for MockContext {
+        type Error = ();
+
+        fn endianness(&self) -> Endianness {
+            Endianness::Little
+        }
+
+        fn read_addrs(&mut self, addr: u64, data: &mut [u8]) -> Result<(), AgentError<()>> {
+            for (i, b) in data.iter_mut().enumerate() {
+                let addr = addr + i as u64;
+                if addr > 0xff {
+                    return Err(AgentError::MemoryAccess(addr));
+                }
+                *b = addr as u8;
+            }
+            Ok(())
+        }
+
+        fn read_register(&mut self, regno: u16, dst: &mut [u8]) -> Result<usize, AgentError<()>> {
+            if regno >= 4 {
+                return Err(AgentError::InvalidRegister(regno));
+            }
+            dst[..4].copy_from_slice(&(regno as u32 * 0x11).to_le_bytes());
+            Ok(4)
+        }
+
+        fn trace(&mut self, addr: u64, len: usize) -> Result<(), AgentError<()>> {
+            self.traced.push((addr, len));
+            Ok(())
+        }
+    }
+
+    fn eval_test(bytecode: &[u8]) -> Result<u64, AgentError<()>> {
+        let mut ctx = MockContext { traced: Vec::new() };
+        eval(&mut ctx, bytecode, &mut [0; 8])
+    }
+
+    #[test]
+    fn arithmetic() {
+        // (3 - 5) * 4 / -2 == 4
+        let bytecode = [
+            0x22, 3, 0x22, 5, 0x03, 0x22, 4, 0x04, 0x22, 2, 0x16, 8, 0x12, 0x22, 1, 0x02, 0x05,
+            0x27,
+        ];
+        assert_eq!(eval_test(&bytecode), Ok(4));
+    }
+
+    #[test]
+    fn registers_and_memory() {
+        // *(u16 *)(r2 + 0x10) == 0x3332
+        let bytecode = [0x26, 0, 2, 0x22, 0x10, 0x02, 0x18, 0x27];
+        assert_eq!(eval_test(&bytecode), Ok(0x3332));
+
+        let bytecode = [0x26, 0, 9, 0x27];
+        assert_eq!(eval_test(&bytecode), Err(AgentError::InvalidRegister(9)));
+    }
+
+    #[test]
+    fn branches() {
+        // r1 == 0x11 ? 7 : 9
+        let bytecode = [
+            0x26, 0, 1, 0x22, 0x11, 0x13, 0x20, 0, 12, 0x22, 9, 0x27, 0x22, 7, 0x27,
+        ];
+        assert_eq!(eval_test(&bytecode), Ok(7));
+    }
+
+    #[test]
+    fn trace() {
+        let mut ctx = MockContext { traced: Vec::new() };
+        // trace 4 bytes at 0x10, and a NUL-terminated string at 0x00
+        let bytecode = [
+            0x22, 0x10, 0x22, 4, 0x0c, 0x22, 0, 0x22, 8, 0x2f, 0x22, 1, 0x27,
+        ];
+        assert_eq!(eval(&mut ctx, &bytecode, &mut [0; 8]), Ok(1));
+        assert_eq!(ctx.traced, &[(0x10, 4), (0x00, 1)]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval_test(&[0x22, 1]), Err(AgentError::UnexpectedEof));
+        assert_eq!(eval_test(&[0x02]), Err(AgentError::StackUnderflow));
+        assert_eq!(eval_test(&[0x28; 1]), Err(AgentError::StackUnderflow));
+        assert_eq!(
+            eval_test(&[0x22, 1, 0x22, 0, 0x06]),
+            Err(AgentError::DivideByZero)
+        );
+        assert_eq!(
+            eval_test(&[0x22, 1, 0x1b]),
+            Err(AgentError::UnsupportedOpcode(0x1b))
+        );
+        assert_eq!(eval_test(&[0xff]), Err(AgentError::InvalidOpcode(0xff)));
+        assert_eq!(
+            eval_test(&[0x22, 1, 0x2c, 0, 1]),
+            Err(AgentError::InvalidTraceStateVariable(1))
+        );
+        assert_eq!(
+            eval_test(&[0x22, 1, 0x28, 0x28, 0x28, 0x28, 0x28, 0x28, 0x28, 0x28]),
+            Err(AgentError::StackOverflow)
+        );
+    }
+
+    #[test]
+    fn infinite_loop() {
+        // goto 0
+        assert_eq!(eval_test(&[0x21, 0, 0]), Err(AgentError::TooManySteps));
+        // if (1) goto 0
+        assert_eq!(
+            eval_test(&[0x22, 1, 0x20, 0, 0]),
+            Err(AgentError::TooManySteps)
+        );
+    }
+}
@@ -15,4 +15,8 @@ impl Arch for Armv4t {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>armv4t</architecture></target>"#)
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
 }
@@ -37,6 +37,10 @@ impl<RegIdImpl: RegId> Arch for Mips<RegIdImpl> {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>mips</architecture></target>"#)
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
 }
 
 impl<RegIdImpl: RegId> Arch for Mips64<RegIdImpl> {
@@ -47,6 +51,10 @@ impl<RegIdImpl: RegId> Arch for Mips64<RegIdImpl> {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>mips64</architecture></target>"#)
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
 }
 
 impl Arch for MipsWithDsp {
@@ -59,6 +67,10 @@ impl Arch for MipsWithDsp {
             r#"<target version="1.0"><architecture>mips</architecture><feature name="org.gnu.gdb.mips.dsp"></feature></target>"#,
         )
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.core.pc)
+    }
 }
 
 impl Arch for Mips64WithDsp {
@@ -71,4 +83,8 @@ impl Arch for Mips64WithDsp {
             r#"<target version="1.0"><architecture>mips64</architecture><feature name="org.gnu.gdb.mips.dsp"></feature></target>"#,
         )
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.core.pc)
+    }
 }
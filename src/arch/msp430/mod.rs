@@ -22,4 +22,9 @@ impl<RegIdImpl: RegId> Arch for Msp430<RegIdImpl> {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>msp430</architecture></target>"#)
     }
+
+    // `Msp430` uses a 32-bit `Usize`, so the 16-bit PC is zero-extended.
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc.into())
+    }
 }
@@ -1,7 +1,7 @@
 //! Implementations for various PowerPC architectures.
 
-use crate::arch::Arch;
 use crate::arch::RegId;
+use crate::arch::{Arch, Endianness};
 
 pub mod reg;
 
@@ -24,4 +24,12 @@ impl<RegIdImpl: RegId> Arch for PowerPcAltivec32<RegIdImpl> {
             r#"<target version="1.0"><architecture>powerpc:common</architecture><feature name="org.gnu.gdb.power.core"></feature><feature name="org.gnu.gdb.power.fpu"></feature><feature name="org.gnu.gdb.power.altivec"></feature></target>"#,
         )
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
+
+    fn endianness() -> Endianness {
+        Endianness::Big
+    }
 }
@@ -20,6 +20,10 @@ impl Arch for Riscv32 {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>riscv</architecture></target>"#)
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
 }
 
 impl Arch for Riscv64 {
@@ -30,4 +34,8 @@ impl Arch for Riscv64 {
     fn target_description_xml() -> Option<&'static str> {
         Some(r#"<target version="1.0"><architecture>riscv64</architecture></target>"#)
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.pc)
+    }
 }
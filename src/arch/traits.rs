@@ -40,6 +40,15 @@ pub trait Registers: Default + Debug + Clone + PartialEq {
     fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()>;
 }
 
+/// The byte order of an architecture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
 /// Encodes architecture-specific information, such as pointer size, register
 /// layout, etc...
 ///
@@ -82,4 +91,26 @@ pub trait Arch {
     fn target_description_xml() -> Option<&'static str> {
         None
     }
+
+    /// (optional) Return the value of the program counter / instruction
+    /// pointer stored in `regs`.
+    ///
+    /// `gdbstub` uses this to determine which breakpoint a target stopped at
+    /// (e.g: when evaluating target-side breakpoint conditions). Target-side
+    /// breakpoint conditions are only supported if this method returns `Some`.
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        let _ = regs;
+        None
+    }
+
+    /// (optional) Return the architecture's byte order.
+    ///
+    /// `gdbstub` uses this to interpret raw memory and register values read
+    /// from the target (e.g: when evaluating target-side breakpoint
+    /// conditions).
+    ///
+    /// Defaults to [`Endianness::Little`].
+    fn endianness() -> Endianness {
+        Endianness::Little
+    }
 }
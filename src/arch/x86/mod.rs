@@ -25,6 +25,10 @@ impl<RegIdImpl: RegId> Arch for X86_64_SSE<RegIdImpl> {
             r#"<target version="1.0"><architecture>i386:x86-64</architecture><feature name="org.gnu.gdb.i386.sse"></feature></target>"#,
         )
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.rip)
+    }
 }
 
 /// Implements `Arch` for 32-bit x86 + SSE Extensions.
@@ -47,4 +51,8 @@ impl<RegIdImpl: RegId> Arch for X86_SSE<RegIdImpl> {
             r#"<target version="1.0"><architecture>i386:intel</architecture><feature name="org.gnu.gdb.i386.sse"></feature></target>"#,
         )
     }
+
+    fn pc(regs: &Self::Registers) -> Option<Self::Usize> {
+        Some(regs.eip)
+    }
 }
@@ -29,8 +29,10 @@ pub enum GdbStubError<T, C> {
     TargetError(T),
     /// Target didn't report any active threads.
     NoActiveThreads,
-    /// Resuming with a signal is not implemented yet. Consider opening a PR?
-    ResumeWithSignalUnimplemented,
+    /// Target reported a stop reason which isn't supported by its current
+    /// configuration (e.g: a syscall request from a target which doesn't
+    /// implement the `FileIo` extension, or while running in non-stop mode).
+    UnsupportedStopReason,
     /// Internal - A non-fatal error occurred (with errno-style error code)
     #[doc(hidden)]
     NonFatalError(u8),
@@ -66,7 +68,7 @@ where
             TargetMismatch => write!(f, "GDB client sent a packet with too much data for the given target."),
             TargetError(e) => write!(f, "Target threw a fatal error: {:?}", e),
             NoActiveThreads => write!(f, "Target didn't report any active threads."),
-            ResumeWithSignalUnimplemented => write!(f, "Resuming with a signal is not implemented yet. Consider opening a PR?"),
+            UnsupportedStopReason => write!(f, "Target reported a stop reason which isn't supported by its current configuration."),
             NonFatalError(_) => write!(f, "Internal - A non-fatal error occurred (with errno-style error code)"),
         }
     }
@@ -1,10 +1,13 @@
 use core::marker::PhantomData;
 
 #[cfg(feature = "alloc")]
-use alloc::collections::BTreeMap;
+use alloc::{collections::BTreeMap, vec::Vec};
 
 use managed::ManagedSlice;
+use num_traits::PrimInt;
 
+#[cfg(feature = "alloc")]
+use crate::agent::{self, AgentError};
 use crate::common::*;
 use crate::{
     arch::{Arch, RegId, Registers},
@@ -12,10 +15,12 @@ use crate::{
     internal::*,
     protocol::{
         commands::{ext, Command},
-        ConsoleOutput, IdKind, Packet, ResponseWriter, ThreadId,
+        ConsoleOutput, IdKind, Packet, ResponseWriter, ResponseWriterError, ThreadId,
     },
+    target::ext::base::multiprocess::{self, ProcessStopReason, ThreadSelector},
     target::ext::base::multithread::{Actions, ResumeAction, ThreadStopReason, TidSelector},
     target::ext::base::BaseOps,
+    target::ext::file_io::Syscall,
     target::Target,
     util::managed_vec::ManagedVec,
     FAKE_PID, SINGLE_THREAD_TID,
@@ -24,11 +29,15 @@ use crate::{
 mod builder;
 mod error;
 mod target_result_ext;
+#[cfg(all(test, feature = "alloc"))]
+mod tests;
+mod xfer_writer;
 
 pub use builder::{GdbStubBuilder, GdbStubBuilderError};
 pub use error::GdbStubError;
 
 use target_result_ext::TargetResultExt;
+use xfer_writer::XferWriter;
 
 use GdbStubError as Error;
 
@@ -83,9 +92,21 @@ struct GdbStubImpl<T: Target, C: Connection> {
     _connection: PhantomData<C>,
 
     packet_buffer_len: usize,
+    current_mem_pid: Pid,
     current_mem_tid: Tid,
-    current_resume_tid: TidSelector,
+    current_resume_tid: ThreadSelector,
     no_ack_mode: bool,
+    // Whether GDB accepts `b`-prefixed binary responses to `x` packets. Other clients (e.g: LLDB)
+    // expect the binary data to be sent as-is.
+    binary_upload: bool,
+    non_stop: bool,
+    vstopped_seq: VStoppedSeq,
+    // The resume action(s) which led to an outstanding File-I/O syscall request, which are re-used
+    // to resume the target once GDB replies with the syscall's result.
+    syscall_resume: Option<SyscallResume<<T::Arch as Arch>::Usize>>,
+    // GDB only marks the first "while-stepping" action of a tracepoint with an `S`, so subsequent
+    // actions need to be tracked across `QTDP` packets.
+    tdp_while_stepping: bool,
 
     // Used to track which Pids were attached to / spawned when running in extended mode.
     //
@@ -94,6 +115,102 @@ struct GdbStubImpl<T: Target, C: Connection> {
     // state structure whether or not extended mode is actually being used.
     #[cfg(feature = "alloc")]
     attached_pids: BTreeMap<Pid, bool>,
+
+    #[cfg(feature = "alloc")]
+    bp_conds: BpConds<<T::Arch as Arch>::Usize>,
+    // Threads which are stepping over a breakpoint whose conditions were false (only used when
+    // running in non-stop mode).
+    #[cfg(feature = "alloc")]
+    step_overs: BTreeMap<Tid, StepOver<<T::Arch as Arch>::Usize>>,
+    // Stopped threads which have yet to be reported in response to `?` / `vStopped` packets (in
+    // reverse order).
+    #[cfg(feature = "alloc")]
+    stopped_threads: Vec<(Pid, Tid)>,
+}
+
+/// Tracks which stop replies GDB retrieves when it sends `vStopped` packets
+/// (only used when running in non-stop mode).
+#[derive(Clone, Copy)]
+enum VStoppedSeq {
+    /// Nothing to report.
+    Idle,
+    /// A `%Stop` notification is waiting to be acknowledged.
+    Notification,
+    /// A `%Stop` notification reporting that the given thread is stopped (e.g:
+    /// after attaching to a new process) has yet to be sent.
+    Pending(Pid, Tid),
+    /// Reporting the state of all threads in response to a `?` packet. Contains
+    /// the thread which was most recently reported.
+    QuestionMark(Pid, Tid),
+}
+
+/// Resume actions saved while waiting for GDB to reply to a File-I/O syscall
+/// request.
+struct SyscallResume<U> {
+    #[cfg(feature = "alloc")]
+    actions: Vec<(ThreadSelector, ResumeAction<U>)>,
+    // In all-stop mode, GDB's `vCont` packets rarely include anything beyond a thread-specific
+    // action and a default action, so without `alloc`, only the first two actions are saved.
+    #[cfg(not(feature = "alloc"))]
+    actions: [Option<(ThreadSelector, ResumeAction<U>)>; 2],
+    #[cfg(not(feature = "alloc"))]
+    len: usize,
+}
+
+impl<U: Copy> SyscallResume<U> {
+    fn new() -> SyscallResume<U> {
+        SyscallResume {
+            #[cfg(feature = "alloc")]
+            actions: Vec::new(),
+            #[cfg(not(feature = "alloc"))]
+            actions: [None; 2],
+            #[cfg(not(feature = "alloc"))]
+            len: 0,
+        }
+    }
+
+    /// Save an action, returning `false` if there's no space left.
+    fn push(&mut self, action: (ThreadSelector, ResumeAction<U>)) -> bool {
+        #[cfg(feature = "alloc")]
+        {
+            self.actions.push(action);
+            true
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        match self.actions.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some(action);
+                self.len += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (ThreadSelector, ResumeAction<U>)> + '_ {
+        #[cfg(feature = "alloc")]
+        return self.actions.iter().copied();
+
+        #[cfg(not(feature = "alloc"))]
+        return self.actions.iter().flatten().copied();
+    }
+}
+
+/// Target-side breakpoint conditions (as agent expression bytecode), keyed by
+/// breakpoint kind (i.e: the `Z` packet type) and address.
+#[cfg(feature = "alloc")]
+type BpConds<U> = BTreeMap<(u8, U), Vec<Vec<u8>>>;
+
+/// A breakpoint whose target-side conditions were all false, which the thread
+/// that hit it has to step over before being resumed.
+#[derive(Clone, Copy)]
+struct StepOver<U> {
+    pid: Pid,
+    tid: Tid,
+    /// The `Z` packet type the breakpoint was inserted with.
+    kind: u8,
+    addr: U,
 }
 
 enum HandlerStatus {
@@ -109,16 +226,28 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             _connection: PhantomData,
 
             packet_buffer_len,
-            // HACK: current_mem_tid is immediately updated with valid value once `run` is called.
-            // While the more idiomatic way to handle this would be to use an Option, given that
-            // it's only ever unset prior to the start of `run`, it's probably okay leaving it as-is
-            // for code-clarity purposes.
+            // HACK: current_mem_pid/tid are immediately updated with valid values once `run` is
+            // called. While the more idiomatic way to handle this would be to use an Option, given
+            // that they're only ever unset prior to the start of `run`, it's probably okay leaving
+            // them as-is for code-clarity purposes.
+            current_mem_pid: FAKE_PID,
             current_mem_tid: SINGLE_THREAD_TID,
-            current_resume_tid: TidSelector::All,
+            current_resume_tid: ThreadSelector::All,
             no_ack_mode: false,
+            binary_upload: false,
+            non_stop: false,
+            vstopped_seq: VStoppedSeq::Idle,
+            syscall_resume: None,
+            tdp_while_stepping: false,
 
             #[cfg(feature = "alloc")]
             attached_pids: BTreeMap::new(),
+            #[cfg(feature = "alloc")]
+            bp_conds: BTreeMap::new(),
+            #[cfg(feature = "alloc")]
+            step_overs: BTreeMap::new(),
+            #[cfg(feature = "alloc")]
+            stopped_threads: Vec::new(),
         }
     }
 
@@ -130,33 +259,62 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
     ) -> Result<DisconnectReason, Error<T::Error, C::Error>> {
         conn.on_session_start().map_err(Error::ConnectionRead)?;
 
-        // before even accepting packets, we query the target to get a sane value for
-        // `self.current_mem_tid`.
-        // NOTE: this will break if extended mode is ever implemented...
+        // before even accepting packets, we query the target to get sane values for
+        // `self.current_mem_pid` and `self.current_mem_tid`.
+        let mut first_thread = None;
+        Self::list_active_threads(target, &mut |pid, tid| {
+            if first_thread.is_none() {
+                first_thread = Some((pid, tid));
+            }
+        })?;
+        let (pid, tid) = first_thread.ok_or(Error::NoActiveThreads)?;
+        self.current_mem_pid = pid;
+        self.current_mem_tid = tid;
 
-        self.current_mem_tid = match target.base_ops() {
-            BaseOps::SingleThread(_) => SINGLE_THREAD_TID,
-            BaseOps::MultiThread(ops) => {
-                let mut first_tid = None;
-                ops.list_active_threads(&mut |tid| {
-                    if first_tid.is_none() {
-                        first_tid = Some(tid);
+        loop {
+            // when running in non-stop mode, the target keeps running in the background while
+            // waiting for packets from GDB, with any stop events being reported asynchronously.
+            if self.non_stop {
+                let stop_reason = match self.vstopped_seq {
+                    VStoppedSeq::Idle => self.poll_non_stop(target, conn)?,
+                    VStoppedSeq::Pending(pid, tid) => Some(ProcessStopReason::SignalWithThread {
+                        pid,
+                        tid,
+                        signal: 0,
+                    }),
+                    VStoppedSeq::Notification | VStoppedSeq::QuestionMark(..) => None,
+                };
+
+                if let Some(stop_reason) = stop_reason {
+                    let mut res = ResponseWriter::new_notification(conn);
+                    res.write_str("Stop:")?;
+                    let disconnect = self.finish_vcont(target, stop_reason, &mut res)?;
+                    res.flush()?;
+
+                    self.vstopped_seq = VStoppedSeq::Notification;
+                    if let Some(disconnect_reason) = disconnect {
+                        return Ok(disconnect_reason);
                     }
-                })
-                .map_err(Error::TargetError)?;
-                first_tid.ok_or(Error::NoActiveThreads)?
+                }
             }
-        };
 
-        loop {
             match Self::recv_packet(conn, target, packet_buffer)? {
                 Packet::Ack => {}
                 Packet::Nack => return Err(Error::ClientSentNack),
                 Packet::Interrupt => {
                     debug!("<-- interrupt packet");
-                    let mut res = ResponseWriter::new(conn);
-                    res.write_str("S05")?;
-                    res.flush()?;
+                    if self.non_stop {
+                        // treated like a `vCtrlC`, except that there is no reply, as the stopped
+                        // threads are reported via the usual asynchronous notifications
+                        Self::resume_non_stop(
+                            target,
+                            &mut core::iter::once((ThreadSelector::All, ResumeAction::Stop)),
+                        )?;
+                    } else {
+                        let mut res = ResponseWriter::new(conn);
+                        res.write_str("S05")?;
+                        res.flush()?;
+                    }
                 }
                 Packet::Command(command) => {
                     // Acknowledge the command
@@ -164,6 +322,9 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         conn.write(b'+').map_err(Error::ConnectionRead)?;
                     }
 
+                    // unlike `vKill`, a plain `k` packet doesn't get a reply
+                    let is_plain_kill = matches!(command, Command::Base(ext::Base::k(_)));
+
                     let mut res = ResponseWriter::new(conn);
                     let disconnect = match self.handle_command(&mut res, target, command) {
                         Ok(HandlerStatus::Handled) => None,
@@ -193,7 +354,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     };
 
                     // HACK: this could be more elegant...
-                    if disconnect != Some(DisconnectReason::Kill) {
+                    if !(is_plain_kill && disconnect == Some(DisconnectReason::Kill)) {
                         res.flush()?;
                     }
 
@@ -231,6 +392,15 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             buf.push(conn.read().map_err(Error::ConnectionRead)?)?;
         }
 
+        // Some commands re-use the free space after the packet as scratch space when building
+        // their response (e.g: `m`, `qXfer`), so owned buffers are padded out to their full
+        // capacity.
+        #[cfg(feature = "alloc")]
+        if let ManagedSlice::Owned(buf) = pkt_buf {
+            let capacity = buf.capacity();
+            buf.resize(capacity, 0);
+        }
+
         match Packet::from_buf(target, pkt_buf.as_mut()) {
             Ok(packet) => Ok(packet),
             Err(e) => Err(Error::PacketParse(e)),
@@ -252,6 +422,18 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             Command::ExtendedMode(cmd) => self.handle_extended_mode(res, target, cmd),
             Command::MonitorCmd(cmd) => self.handle_monitor_cmd(res, target, cmd),
             Command::SectionOffsets(cmd) => self.handle_section_offsets(res, target, cmd),
+            Command::FileIo(cmd) => self.handle_file_io(res, target, cmd),
+            Command::MemoryMap(cmd) => self.handle_memory_map(res, target, cmd),
+            Command::Flash(cmd) => self.handle_flash(res, target, cmd),
+            Command::Libraries(cmd) => self.handle_libraries(res, target, cmd),
+            Command::LibrariesSvr4(cmd) => self.handle_libraries_svr4(res, target, cmd),
+            Command::Auxv(cmd) => self.handle_auxv(res, target, cmd),
+            Command::ExecFile(cmd) => self.handle_exec_file(res, target, cmd),
+            Command::SigInfo(cmd) => self.handle_sig_info(res, target, cmd),
+            Command::OsData(cmd) => self.handle_os_data(res, target, cmd),
+            Command::BranchTrace(cmd) => self.handle_branch_trace(res, target, cmd),
+            Command::HostIo(cmd) => self.handle_host_io(res, target, cmd),
+            Command::Tracepoints(cmd) => self.handle_tracepoints(res, target, cmd),
         }
     }
 
@@ -266,7 +448,16 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             ext::Base::qSupported(cmd) => {
                 // XXX: actually read what the client supports, and enable/disable features
                 // appropriately
-                let _features = cmd.features.into_iter();
+                use crate::protocol::commands::_qSupported::FeatureSupported;
+
+                self.binary_upload = false;
+                for feature in cmd.features.into_iter().flatten() {
+                    if let (b"binary-upload", FeatureSupported::Yes) =
+                        (feature.name.as_ref(), feature.status)
+                    {
+                        self.binary_upload = true;
+                    }
+                }
 
                 res.write_str("PacketSize=")?;
                 res.write_num(self.packet_buffer_len)?;
@@ -274,6 +465,19 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 res.write_str(";vContSupported+")?;
                 res.write_str(";multiprocess+")?;
                 res.write_str(";QStartNoAckMode+")?;
+                res.write_str(";binary-upload+")?;
+
+                if Self::supports_non_stop(target) {
+                    res.write_str(";QNonStop+")?;
+                }
+
+                if Self::supports_reverse_step(target) {
+                    res.write_str(";ReverseStep+")?;
+                }
+
+                if Self::supports_reverse_cont(target) {
+                    res.write_str(";ReverseContinue+")?;
+                }
 
                 if let Some(ops) = target.extended_mode() {
                     if ops.configure_aslr().is_some() {
@@ -300,36 +504,81 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     res.write_str(";hwbreak+")?;
                 }
 
-                // TODO: implement conditional breakpoint support (since that's kool).
-                // res.write_str("ConditionalBreakpoints+;")?;
+                // conditions are stored on the heap, so they're only supported with `alloc`, and
+                // the PC is required to tell which breakpoint a thread stopped at
+                #[cfg(feature = "alloc")]
+                if (target.sw_breakpoint().is_some() || target.hw_breakpoint().is_some())
+                    && <T::Arch as Arch>::pc(&Default::default()).is_some()
+                {
+                    res.write_str(";ConditionalBreakpoints+")?;
+                }
+
+                if target.tracepoints().is_some() {
+                    res.write_str(";ConditionalTracepoints+")?;
+                    res.write_str(";tracenz+")?;
+                }
 
                 if T::Arch::target_description_xml().is_some() {
                     res.write_str(";qXfer:features:read+")?;
                 }
 
+                if target.memory_map().is_some() {
+                    res.write_str(";qXfer:memory-map:read+")?;
+                }
+
+                if target.libraries().is_some() {
+                    res.write_str(";qXfer:libraries:read+")?;
+                }
+
+                if target.libraries_svr4().is_some() {
+                    res.write_str(";qXfer:libraries-svr4:read+")?;
+                }
+
+                if target.auxv().is_some() {
+                    res.write_str(";qXfer:auxv:read+")?;
+                }
+
+                if target.exec_file().is_some() {
+                    res.write_str(";qXfer:exec-file:read+")?;
+                }
+
+                if target.os_data().is_some() {
+                    res.write_str(";qXfer:osdata:read+")?;
+                }
+
+                if target.branch_trace().is_some() {
+                    res.write_str(";Qbtrace:bts+;Qbtrace:off+")?;
+                    res.write_str(";qXfer:btrace:read+;qXfer:btrace-conf:read+")?;
+                }
+
+                if let Some(ops) = target.sig_info() {
+                    res.write_str(";qXfer:siginfo:read+")?;
+                    if ops.support_write().is_some() {
+                        res.write_str(";qXfer:siginfo:write+")?;
+                    }
+                }
+
+                if Self::supports_threads_info(target) {
+                    res.write_str(";qXfer:threads:read+")?;
+                }
+
                 HandlerStatus::Handled
             }
             ext::Base::QStartNoAckMode(_) => {
                 self.no_ack_mode = true;
                 HandlerStatus::NeedsOK
             }
+            ext::Base::QNonStop(cmd) => {
+                if cmd.enable && !Self::supports_non_stop(target) {
+                    return Err(Error::NonFatalError(1));
+                }
+                self.non_stop = cmd.enable;
+                self.vstopped_seq = VStoppedSeq::Idle;
+                HandlerStatus::NeedsOK
+            }
             ext::Base::qXferFeaturesRead(cmd) => {
                 match T::Arch::target_description_xml() {
-                    Some(xml) => {
-                        let xml = xml.trim();
-                        if cmd.offset >= xml.len() {
-                            // no more data
-                            res.write_str("l")?;
-                        } else if cmd.offset + cmd.len >= xml.len() {
-                            // last little bit of data
-                            res.write_str("l")?;
-                            res.write_binary(&xml.as_bytes()[cmd.offset..])?
-                        } else {
-                            // still more data
-                            res.write_str("m")?;
-                            res.write_binary(&xml.as_bytes()[cmd.offset..(cmd.offset + cmd.len)])?
-                        }
-                    }
+                    Some(xml) => write_xfer_chunk(res, xml.trim().as_bytes(), cmd.offset, cmd.len)?,
                     // If the target hasn't provided their own XML, then the initial response to
                     // "qSupported" wouldn't have included  "qXfer:features:read", and gdb wouldn't
                     // send this packet unless it was explicitly marked as supported.
@@ -338,12 +587,102 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 HandlerStatus::Handled
             }
 
+            ext::Base::qXferThreadsRead(cmd) => {
+                // If the target doesn't support `ThreadsInfo`, then the initial response
+                // to "qSupported" wouldn't have included "qXfer:threads:read".
+                let ops = match target.base_ops() {
+                    BaseOps::MultiThread(ops) => ops.support_threads_info(),
+                    BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => None,
+                }
+                .ok_or(Error::PacketUnexpected)?;
+
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
+
+                xml.write_str("<threads>");
+                ops.list_threads_info(&mut |tid, info| {
+                    xml.write_str("<thread id=\"p");
+                    xml.write_num(FAKE_PID.get());
+                    xml.write_str(".");
+                    xml.write_num(tid.get());
+                    if let Some(core) = info.core {
+                        xml.write_str("\" core=\"");
+                        xml.write_dec(core as u64);
+                    }
+                    if let Some(name) = info.name {
+                        xml.write_str("\" name=\"");
+                        xml.write_xml_escaped(name);
+                    }
+                    xml.write_str("\">");
+                    if let Some(extra) = info.extra {
+                        xml.write_xml_escaped(extra);
+                    }
+                    xml.write_str("</thread>");
+                })
+                .map_err(Error::TargetError)?;
+                xml.write_str("</threads>");
+
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
+            }
+
             // -------------------- "Core" Functionality -------------------- //
             // TODO: Improve the '?' response based on last-sent stop reason.
             ext::Base::QuestionMark(_) => {
-                res.write_str("S05")?;
-                HandlerStatus::Handled
+                if self.non_stop {
+                    // report each stopped thread, one at a time (with the remaining threads being
+                    // retrieved via `vStopped`).
+                    match self.next_stopped_thread(target, None)? {
+                        Some((pid, tid)) => {
+                            self.vstopped_seq = VStoppedSeq::QuestionMark(pid, tid);
+                            self.finish_vcont(
+                                target,
+                                ProcessStopReason::SignalWithThread {
+                                    pid,
+                                    tid,
+                                    signal: 0,
+                                },
+                                res,
+                            )?;
+                            HandlerStatus::Handled
+                        }
+                        None => HandlerStatus::NeedsOK,
+                    }
+                } else {
+                    res.write_str("S05")?;
+                    HandlerStatus::Handled
+                }
             }
+            ext::Base::vStopped(_) => match self.vstopped_seq {
+                VStoppedSeq::QuestionMark(prev_pid, prev_tid) => {
+                    match self.next_stopped_thread(target, Some((prev_pid, prev_tid)))? {
+                        Some((pid, tid)) => {
+                            self.vstopped_seq = VStoppedSeq::QuestionMark(pid, tid);
+                            self.finish_vcont(
+                                target,
+                                ProcessStopReason::SignalWithThread {
+                                    pid,
+                                    tid,
+                                    signal: 0,
+                                },
+                                res,
+                            )?;
+                            HandlerStatus::Handled
+                        }
+                        None => {
+                            self.vstopped_seq = VStoppedSeq::Idle;
+                            HandlerStatus::NeedsOK
+                        }
+                    }
+                }
+                // Stop events are only pulled from the target once the previous notification
+                // has been acknowledged, so there is never more than one event in flight.
+                VStoppedSeq::Notification | VStoppedSeq::Idle => {
+                    self.vstopped_seq = VStoppedSeq::Idle;
+                    HandlerStatus::NeedsOK
+                }
+                // the pending notification hasn't been sent yet
+                VStoppedSeq::Pending(..) => HandlerStatus::NeedsOK,
+            },
             ext::Base::qAttached(cmd) => {
                 let is_attached = match target.extended_mode() {
                     // when _not_ running in extended mode, just report that we're attaching to an
@@ -375,6 +714,9 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     BaseOps::MultiThread(ops) => {
                         ops.read_registers(&mut regs, self.current_mem_tid)
                     }
+                    BaseOps::MultiProcess(ops) => {
+                        ops.read_registers(&mut regs, self.current_mem_pid, self.current_mem_tid)
+                    }
                 }
                 .handle_error()?;
 
@@ -399,18 +741,31 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 match target.base_ops() {
                     BaseOps::SingleThread(ops) => ops.write_registers(&regs),
                     BaseOps::MultiThread(ops) => ops.write_registers(&regs, self.current_mem_tid),
+                    BaseOps::MultiProcess(ops) => {
+                        ops.write_registers(&regs, self.current_mem_pid, self.current_mem_tid)
+                    }
                 }
                 .handle_error()?;
 
                 HandlerStatus::NeedsOK
             }
-            ext::Base::m(cmd) => {
-                let buf = cmd.buf;
-                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
-                    .ok_or(Error::TargetMismatch)?;
+            ext::Base::m(_) | ext::Base::x(_) => {
+                // `m` and `x` only differ in how the response is encoded
+                let (addr, len, buf, binary) = match command {
+                    ext::Base::m(cmd) => (cmd.addr, cmd.len, cmd.buf, false),
+                    ext::Base::x(cmd) => (cmd.addr, cmd.len, cmd.buf, true),
+                    _ => unreachable!(),
+                };
+                let addr =
+                    <T::Arch as Arch>::Usize::from_be_bytes(addr).ok_or(Error::TargetMismatch)?;
+
+                // LLDB probes for `x` support by sending a zero-length read
+                if binary && len == 0 {
+                    return Ok(HandlerStatus::NeedsOK);
+                }
 
                 let mut i = 0;
-                let mut n = cmd.len;
+                let mut n = len;
                 while n != 0 {
                     let chunk_size = n.min(buf.len());
 
@@ -418,29 +773,138 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                     let addr = addr + NumCast::from(i).ok_or(Error::TargetMismatch)?;
                     let data = &mut buf[..chunk_size];
-                    match target.base_ops() {
-                        BaseOps::SingleThread(ops) => ops.read_addrs(addr, data),
-                        BaseOps::MultiThread(ops) => {
-                            ops.read_addrs(addr, data, self.current_mem_tid)
+                    self.read_addrs(target, addr, data)?;
+
+                    if binary {
+                        // GDB expects binary data to be prefixed with a `b`, which is only
+                        // written once the first chunk was read successfully, so that any
+                        // errors are reported as a plain `E` response
+                        if i == 0 && self.binary_upload {
+                            res.write_str("b")?;
                         }
+                        res.write_binary(data)?;
+                    } else {
+                        res.write_hex_buf(data)?;
                     }
-                    .handle_error()?;
 
                     n -= chunk_size;
                     i += chunk_size;
-
-                    res.write_hex_buf(data)?;
                 }
                 HandlerStatus::Handled
             }
-            ext::Base::M(cmd) => {
+            ext::Base::qCRC(cmd) => {
+                let buf = cmd.buf;
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                let crc = match target.memory_crc() {
+                    Some(ops) => ops
+                        .crc32(self.current_mem_pid, self.current_mem_tid, addr, cmd.len)
+                        .handle_error()?,
+                    None => {
+                        let mut crc = 0xffff_ffff;
+                        let mut i = 0;
+                        let mut n = cmd.len;
+                        while n != 0 {
+                            let chunk_size = n.min(buf.len());
+
+                            use num_traits::NumCast;
+
+                            let addr = addr + NumCast::from(i).ok_or(Error::TargetMismatch)?;
+                            let data = &mut buf[..chunk_size];
+                            self.read_addrs(target, addr, data)?;
+                            crc = crate::util::crc32::crc32(crc, data);
+
+                            n -= chunk_size;
+                            i += chunk_size;
+                        }
+                        crc
+                    }
+                };
+
+                res.write_str("C")?;
+                res.write_num(crc)?;
+                HandlerStatus::Handled
+            }
+            ext::Base::qSearchMemory(cmd) => {
+                let buf = cmd.buf;
+                let pattern = cmd.pattern;
                 let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
                     .ok_or(Error::TargetMismatch)?;
 
+                let found = match target.memory_search() {
+                    Some(ops) => ops
+                        .search_memory(
+                            self.current_mem_pid,
+                            self.current_mem_tid,
+                            addr,
+                            cmd.len,
+                            pattern,
+                        )
+                        .handle_error()?,
+                    None => {
+                        // each chunk must be able to hold at least one full match
+                        if buf.len() < pattern.len() {
+                            return Err(Error::NonFatalError(1));
+                        }
+
+                        let mut found = None;
+                        let mut i = 0;
+                        while cmd.len - i >= pattern.len() {
+                            let chunk_size = (cmd.len - i).min(buf.len());
+
+                            use num_traits::NumCast;
+
+                            let chunk_addr =
+                                addr + NumCast::from(i).ok_or(Error::TargetMismatch)?;
+                            let data = &mut buf[..chunk_size];
+                            self.read_addrs(target, chunk_addr, data)?;
+
+                            if let Some(pos) =
+                                data.windows(pattern.len()).position(|w| w == pattern)
+                            {
+                                found = Some(
+                                    chunk_addr + NumCast::from(pos).ok_or(Error::TargetMismatch)?,
+                                );
+                                break;
+                            }
+
+                            if i + chunk_size == cmd.len {
+                                break;
+                            }
+
+                            // overlap consecutive chunks, so that matches which straddle a chunk
+                            // boundary aren't missed
+                            i += chunk_size - (pattern.len() - 1);
+                        }
+                        found
+                    }
+                };
+
+                match found {
+                    Some(addr) => {
+                        res.write_str("1,")?;
+                        res.write_num(addr)?;
+                    }
+                    None => res.write_str("0")?,
+                }
+                HandlerStatus::Handled
+            }
+            ext::Base::M(_) | ext::Base::X(_) => {
+                // `M` and `X` only differ in how the data is encoded on the wire
+                let (addr, val) = match command {
+                    ext::Base::M(cmd) => (cmd.addr, cmd.val),
+                    ext::Base::X(cmd) => (cmd.addr, cmd.val),
+                    _ => unreachable!(),
+                };
+                let addr =
+                    <T::Arch as Arch>::Usize::from_be_bytes(addr).ok_or(Error::TargetMismatch)?;
+
                 match target.base_ops() {
-                    BaseOps::SingleThread(ops) => ops.write_addrs(addr, cmd.val),
-                    BaseOps::MultiThread(ops) => {
-                        ops.write_addrs(addr, cmd.val, self.current_mem_tid)
+                    BaseOps::SingleThread(ops) => ops.write_addrs(addr, val),
+                    BaseOps::MultiThread(ops) => ops.write_addrs(addr, val, self.current_mem_tid),
+                    BaseOps::MultiProcess(ops) => {
+                        ops.write_addrs(addr, val, self.current_mem_pid, self.current_mem_tid)
                     }
                 }
                 .handle_error()?;
@@ -449,8 +913,54 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             }
             ext::Base::k(_) | ext::Base::vKill(_) => {
                 match target.extended_mode() {
-                    // When not running in extended mode, stop the `GdbStub` and disconnect.
-                    None => HandlerStatus::Disconnect(DisconnectReason::Kill),
+                    // When not running in extended mode, stop the `GdbStub` and disconnect...
+                    None => {
+                        let pid = match command {
+                            ext::Base::vKill(cmd) => Some(cmd.pid),
+                            _ => None,
+                        };
+
+                        match (pid, target.base_ops()) {
+                            // ...unless there are other processes left to debug.
+                            (Some(pid), BaseOps::MultiProcess(ops)) => {
+                                ops.kill(pid).handle_error()?;
+                                self.forget_process(target, pid)?;
+
+                                let mut any_threads = false;
+                                Self::list_active_threads(target, &mut |_, _| any_threads = true)?;
+                                if any_threads {
+                                    HandlerStatus::NeedsOK
+                                } else {
+                                    // manually write OK, since we need to return a
+                                    // DisconnectReason
+                                    res.write_str("OK")?;
+                                    HandlerStatus::Disconnect(DisconnectReason::Kill)
+                                }
+                            }
+                            // a plain `k` kills every process being debugged
+                            (None, BaseOps::MultiProcess(_)) => {
+                                let mut prev_pid = None;
+                                while let Some((pid, _)) = Self::next_active_thread(target, None)? {
+                                    // don't spin forever if the target fails to kill a process
+                                    if prev_pid == Some(pid) {
+                                        break;
+                                    }
+                                    prev_pid = Some(pid);
+
+                                    if let BaseOps::MultiProcess(ops) = target.base_ops() {
+                                        ops.kill(pid).handle_error()?;
+                                    }
+                                }
+                                HandlerStatus::Disconnect(DisconnectReason::Kill)
+                            }
+                            (pid, _) => {
+                                if pid.is_some() {
+                                    res.write_str("OK")?;
+                                }
+                                HandlerStatus::Disconnect(DisconnectReason::Kill)
+                            }
+                        }
+                    }
 
                     // When running in extended mode, a kill command does not necessarily result in
                     // a disconnect...
@@ -471,8 +981,18 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     }
                 }
             }
-            ext::Base::D(_) => {
-                // TODO: plumb-through Pid when exposing full multiprocess + extended mode
+            ext::Base::D(cmd) => {
+                if let (Some(pid), BaseOps::MultiProcess(ops)) = (cmd.pid, target.base_ops()) {
+                    ops.detach(pid).handle_error()?;
+
+                    // only disconnect once there are no more processes left to debug
+                    let mut any_threads = false;
+                    Self::list_active_threads(target, &mut |_, _| any_threads = true)?;
+                    if any_threads {
+                        return Ok(HandlerStatus::NeedsOK);
+                    }
+                }
+
                 res.write_str("OK")?; // manually write OK, since we need to return a DisconnectReason
                 HandlerStatus::Disconnect(DisconnectReason::Disconnect)
             }
@@ -497,7 +1017,23 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         Err(e).handle_error()?;
                         HandlerStatus::Handled
                     }
-                    Some(Ok(true)) => HandlerStatus::NeedsOK,
+                    Some(Ok(true)) => {
+                        // GDB re-sends `Z` packets whenever a breakpoint's conditions change, so
+                        // any existing conditions are replaced.
+                        #[cfg(feature = "alloc")]
+                        if cmd.type_ == 0 || cmd.type_ == 1 {
+                            match cmd.conds {
+                                Some(conds) => {
+                                    let conds = conds.into_iter().map(|c| c.to_vec()).collect();
+                                    self.bp_conds.insert((cmd.type_, addr), conds);
+                                }
+                                None => {
+                                    self.bp_conds.remove(&(cmd.type_, addr));
+                                }
+                            }
+                        }
+                        HandlerStatus::NeedsOK
+                    }
                     Some(Ok(false)) => return Err(Error::NonFatalError(22)),
                 }
             }
@@ -524,7 +1060,13 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         Err(e).handle_error()?;
                         HandlerStatus::Handled
                     }
-                    Some(Ok(true)) => HandlerStatus::NeedsOK,
+                    Some(Ok(true)) => {
+                        #[cfg(feature = "alloc")]
+                        if cmd.type_ == 0 || cmd.type_ == 1 {
+                            self.bp_conds.remove(&(cmd.type_, addr));
+                        }
+                        HandlerStatus::NeedsOK
+                    }
                     Some(Ok(false)) => return Err(Error::NonFatalError(22)),
                 }
             }
@@ -542,6 +1084,9 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     BaseOps::MultiThread(ops) => {
                         ops.read_register(reg_id, dst, self.current_mem_tid)
                     }
+                    BaseOps::MultiProcess(ops) => {
+                        ops.read_register(reg_id, dst, self.current_mem_pid, self.current_mem_tid)
+                    }
                 }
                 .handle_error()?;
 
@@ -557,6 +1102,12 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         BaseOps::MultiThread(ops) => {
                             ops.write_register(reg_id, p.val, self.current_mem_tid)
                         }
+                        BaseOps::MultiProcess(ops) => ops.write_register(
+                            reg_id,
+                            p.val,
+                            self.current_mem_pid,
+                            self.current_mem_tid,
+                        ),
                     }
                     .handle_error()?,
                 }
@@ -568,12 +1119,20 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 let actions = match cmd {
                     vCont::Query => {
                         res.write_str("vCont;c;C;s;S")?;
+                        if Self::supports_non_stop(target) {
+                            res.write_str(";t")?;
+                        }
+                        if Self::supports_range_stepping(target) {
+                            res.write_str(";r")?;
+                        }
                         return Ok(HandlerStatus::Handled);
                     }
                     vCont::Actions(actions) => actions,
                 };
 
                 // map raw vCont action iterator to a format the `Target` expects
+                let non_stop = self.non_stop;
+                let range_stepping = Self::supports_range_stepping(target);
                 let mut err = Ok(());
                 let mut actions = actions.into_iter().filter_map(|action| {
                     let action = match action {
@@ -589,28 +1148,36 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     let resume_action = match action.kind {
                         VContKind::Step => ResumeAction::Step,
                         VContKind::Continue => ResumeAction::Continue,
+                        VContKind::StepWithSig(sig) => ResumeAction::StepWithSignal(sig),
+                        VContKind::ContinueWithSig(sig) => ResumeAction::ContinueWithSignal(sig),
+                        VContKind::Stop if non_stop => ResumeAction::Stop,
+                        VContKind::RangeStep(start, end) if range_stepping => {
+                            let start = <T::Arch as Arch>::Usize::from_be_bytes(start);
+                            let end = <T::Arch as Arch>::Usize::from_be_bytes(end);
+                            match (start, end) {
+                                (Some(start), Some(end)) => ResumeAction::StepInRange(start, end),
+                                _ => {
+                                    err = Err(Error::TargetMismatch);
+                                    return None;
+                                }
+                            }
+                        }
                         _ => {
-                            // there seems to be a GDB bug where it doesn't use `vCont` unless
-                            // `vCont?` returns support for resuming with a signal.
-                            //
-                            // This error case can be removed once "Resume with Signal" is
-                            // implemented
-                            err = Err(Error::ResumeWithSignalUnimplemented);
+                            err = Err(Error::PacketUnexpected);
                             return None;
                         }
                     };
 
                     let tid = match action.thread {
-                        Some(thread) => match thread.tid {
-                            IdKind::Any => {
+                        Some(thread) => match thread_selector(thread) {
+                            Some(selector) => selector,
+                            None => {
                                 err = Err(Error::PacketUnexpected);
                                 return None;
                             }
-                            IdKind::All => TidSelector::All,
-                            IdKind::WithID(tid) => TidSelector::WithID(tid),
                         },
                         // An action with no thread-id matches all threads
-                        None => TidSelector::All,
+                        None => ThreadSelector::All,
                     };
 
                     Some((tid, resume_action))
@@ -624,7 +1191,23 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 err?;
                 ret
             }
-            // TODO?: support custom resume addr in 'c' and 's'
+            ext::Base::vCtrlC(_) => {
+                // only sent in non-stop mode, where it replaces the raw interrupt packet
+                if !self.non_stop {
+                    return Err(Error::PacketUnexpected);
+                }
+
+                match self.do_vcont(
+                    res,
+                    target,
+                    &mut core::iter::once((ThreadSelector::All, ResumeAction::Stop)),
+                ) {
+                    Ok(None) => HandlerStatus::Handled,
+                    Ok(Some(dc)) => HandlerStatus::Disconnect(dc),
+                    Err(e) => return Err(e),
+                }
+            }
+            // TODO?: support custom resume addr in 'c', 'C', 's', and 'S'
             ext::Base::c(_) => {
                 match self.do_vcont(
                     res,
@@ -636,6 +1219,26 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     Err(e) => return Err(e),
                 }
             }
+            ext::Base::C(cmd) => {
+                // rather than silently resuming at the wrong address, custom resume addresses
+                // are rejected outright
+                if cmd.addr.is_some() {
+                    return Err(Error::NonFatalError(22));
+                }
+
+                match self.do_vcont(
+                    res,
+                    target,
+                    &mut core::iter::once((
+                        self.current_resume_tid,
+                        ResumeAction::ContinueWithSignal(cmd.sig),
+                    )),
+                ) {
+                    Ok(None) => HandlerStatus::Handled,
+                    Ok(Some(dc)) => HandlerStatus::Disconnect(dc),
+                    Err(e) => return Err(e),
+                }
+            }
             ext::Base::s(_) => {
                 match self.do_vcont(
                     res,
@@ -647,22 +1250,92 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     Err(e) => return Err(e),
                 }
             }
+            ext::Base::S(cmd) => {
+                // rather than silently resuming at the wrong address, custom resume addresses
+                // are rejected outright
+                if cmd.addr.is_some() {
+                    return Err(Error::NonFatalError(22));
+                }
+
+                match self.do_vcont(
+                    res,
+                    target,
+                    &mut core::iter::once((
+                        self.current_resume_tid,
+                        ResumeAction::StepWithSignal(cmd.sig),
+                    )),
+                ) {
+                    Ok(None) => HandlerStatus::Handled,
+                    Ok(Some(dc)) => HandlerStatus::Disconnect(dc),
+                    Err(e) => return Err(e),
+                }
+            }
+            ext::Base::bs(_) => {
+                // not advertised in "qSupported"
+                if !Self::supports_reverse_step(target) {
+                    return Ok(HandlerStatus::Handled);
+                }
+
+                match self.do_vcont(
+                    res,
+                    target,
+                    &mut core::iter::once((self.current_resume_tid, ResumeAction::ReverseStep)),
+                ) {
+                    Ok(None) => HandlerStatus::Handled,
+                    Ok(Some(dc)) => HandlerStatus::Disconnect(dc),
+                    Err(e) => return Err(e),
+                }
+            }
+            ext::Base::bc(_) => {
+                // not advertised in "qSupported"
+                if !Self::supports_reverse_cont(target) {
+                    return Ok(HandlerStatus::Handled);
+                }
+
+                match self.do_vcont(
+                    res,
+                    target,
+                    &mut core::iter::once((self.current_resume_tid, ResumeAction::ReverseContinue)),
+                ) {
+                    Ok(None) => HandlerStatus::Handled,
+                    Ok(Some(dc)) => HandlerStatus::Disconnect(dc),
+                    Err(e) => return Err(e),
+                }
+            }
 
             // ------------------- Multi-threading Support ------------------ //
             ext::Base::H(cmd) => {
                 use crate::protocol::commands::_h_upcase::Op;
                 match cmd.kind {
-                    Op::Other => match cmd.thread.tid {
-                        IdKind::Any => {} // reuse old tid
+                    Op::Other => match (cmd.thread.pid, cmd.thread.tid) {
+                        // select any thread from a different process
+                        (Some(IdKind::WithID(pid)), IdKind::Any) if pid != self.current_mem_pid => {
+                            let mut tid = None;
+                            Self::list_active_threads(target, &mut |active_pid, active_tid| {
+                                if active_pid == pid && tid.is_none() {
+                                    tid = Some(active_tid);
+                                }
+                            })?;
+                            self.current_mem_pid = pid;
+                            self.current_mem_tid = tid.ok_or(Error::NonFatalError(1))?;
+                        }
+                        (_, IdKind::Any) => {} // reuse old tid
                         // "All" threads doesn't make sense for memory accesses
-                        IdKind::All => return Err(Error::PacketUnexpected),
-                        IdKind::WithID(tid) => self.current_mem_tid = tid,
+                        (_, IdKind::All) => return Err(Error::PacketUnexpected),
+                        (pid, IdKind::WithID(tid)) => {
+                            if let Some(IdKind::WithID(pid)) = pid {
+                                self.current_mem_pid = pid;
+                            }
+                            self.current_mem_tid = tid;
+                        }
                     },
                     // technically, this variant is deprecated in favor of vCont...
                     Op::StepContinue => match cmd.thread.tid {
                         IdKind::Any => {} // reuse old tid
-                        IdKind::All => self.current_resume_tid = TidSelector::All,
-                        IdKind::WithID(tid) => self.current_resume_tid = TidSelector::WithID(tid),
+                        _ => {
+                            self.current_resume_tid =
+                                thread_selector(cmd.thread).ok_or(Error::PacketUnexpected)?
+                        }
                     },
                 }
                 HandlerStatus::NeedsOK
@@ -670,36 +1343,27 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             ext::Base::qfThreadInfo(_) => {
                 res.write_str("m")?;
 
-                match target.base_ops() {
-                    BaseOps::SingleThread(_) => res.write_thread_id(ThreadId {
-                        pid: Some(IdKind::WithID(FAKE_PID)),
-                        tid: IdKind::WithID(SINGLE_THREAD_TID),
-                    })?,
-                    BaseOps::MultiThread(ops) => {
-                        let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
-                        let mut first = true;
-                        ops.list_active_threads(&mut |tid| {
-                            // TODO: replace this with a try block (once stabilized)
-                            let e = (|| {
-                                if !first {
-                                    res.write_str(",")?
-                                }
-                                first = false;
-                                res.write_thread_id(ThreadId {
-                                    pid: Some(IdKind::WithID(FAKE_PID)),
-                                    tid: IdKind::WithID(tid),
-                                })?;
-                                Ok(())
-                            })();
-
-                            if let Err(e) = e {
-                                err = Err(e)
-                            }
-                        })
-                        .map_err(Error::TargetError)?;
-                        err?;
+                let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
+                let mut first = true;
+                Self::list_active_threads(target, &mut |pid, tid| {
+                    // TODO: replace this with a try block (once stabilized)
+                    let e = (|| {
+                        if !first {
+                            res.write_str(",")?
+                        }
+                        first = false;
+                        res.write_thread_id(ThreadId {
+                            pid: Some(IdKind::WithID(pid)),
+                            tid: IdKind::WithID(tid),
+                        })?;
+                        Ok(())
+                    })();
+
+                    if let Err(e) = e {
+                        err = Err(e)
                     }
-                }
+                })?;
+                err?;
 
                 HandlerStatus::Handled
             }
@@ -707,6 +1371,20 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 res.write_str("l")?;
                 HandlerStatus::Handled
             }
+            ext::Base::qThreadExtraInfo(cmd) => {
+                let tid = match cmd.thread.tid {
+                    IdKind::WithID(tid) => tid,
+                    _ => return Err(Error::PacketUnexpected),
+                };
+
+                if let BaseOps::MultiThread(ops) = target.base_ops() {
+                    if let Some(ops) = ops.support_thread_extra_info() {
+                        let n = ops.thread_extra_info(tid, cmd.buf).handle_error()?;
+                        res.write_hex_buf(&cmd.buf[..n.min(cmd.buf.len())])?;
+                    }
+                }
+                HandlerStatus::Handled
+            }
             ext::Base::T(cmd) => {
                 let alive = match cmd.thread.tid {
                     IdKind::WithID(tid) => match target.base_ops() {
@@ -714,6 +1392,13 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         BaseOps::MultiThread(ops) => {
                             ops.is_thread_alive(tid).map_err(Error::TargetError)?
                         }
+                        BaseOps::MultiProcess(ops) => {
+                            let pid = match cmd.thread.pid {
+                                Some(IdKind::WithID(pid)) => pid,
+                                _ => self.current_mem_pid,
+                            };
+                            ops.is_thread_alive(pid, tid).map_err(Error::TargetError)?
+                        }
                     },
                     // TODO: double-check if GDB ever sends other variants
                     // Even after ample testing, this arm has never been hit...
@@ -825,213 +1510,1748 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 
-    fn handle_extended_mode<'a>(
+    fn handle_memory_map(
         &mut self,
         res: &mut ResponseWriter<C>,
         target: &mut T,
-        command: ext::ExtendedMode<'a>,
+        command: ext::MemoryMap,
     ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
-        let ops = match target.extended_mode() {
+        let ops = match target.memory_map() {
             Some(ops) => ops,
             None => return Ok(HandlerStatus::Handled),
         };
 
         let handler_status = match command {
-            ext::ExtendedMode::ExclamationMark(_cmd) => {
-                ops.on_start().map_err(Error::TargetError)?;
-                HandlerStatus::NeedsOK
-            }
-            ext::ExtendedMode::R(_cmd) => {
-                ops.restart().map_err(Error::TargetError)?;
-                HandlerStatus::Handled
-            }
-            ext::ExtendedMode::vAttach(cmd) => {
-                ops.attach(cmd.pid).handle_error()?;
-
-                #[cfg(feature = "alloc")]
-                self.attached_pids.insert(cmd.pid, true);
-
-                // TODO: sends OK when running in Non-Stop mode
+            ext::MemoryMap::qXferMemoryMapRead(cmd) => {
+                let xml = ops.memory_map_xml().trim();
+                write_xfer_chunk(res, xml.as_bytes(), cmd.offset, cmd.len)?;
                 HandlerStatus::Handled
             }
-            ext::ExtendedMode::vRun(cmd) => {
-                use crate::target::ext::extended_mode::Args;
+        };
+        Ok(handler_status)
+    }
 
-                let mut pid = ops
-                    .run(cmd.filename, Args::new(&mut cmd.args.into_iter()))
-                    .handle_error()?;
+    fn handle_flash<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::Flash<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::target::ext::flash::FlashWriteError;
 
-                // on single-threaded systems, we'll ignore the provided PID and keep
-                // using the FAKE_PID.
-                if let BaseOps::SingleThread(_) = target.base_ops() {
-                    pid = FAKE_PID;
-                }
+        let ops = match target.flash() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
 
-                let _ = pid; // squelch warning on no_std targets
-                #[cfg(feature = "alloc")]
-                self.attached_pids.insert(pid, false);
+        let handler_status = match command {
+            ext::Flash::vFlashErase(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+                let length = <T::Arch as Arch>::Usize::from_be_bytes(cmd.length)
+                    .ok_or(Error::TargetMismatch)?;
 
-                // TODO: send a more descriptive stop packet?
-                res.write_str("S05")?;
-                HandlerStatus::Handled
-            }
-            // --------- ASLR --------- //
-            ext::ExtendedMode::QDisableRandomization(cmd) if ops.configure_aslr().is_some() => {
-                let ops = ops.configure_aslr().unwrap();
-                ops.cfg_aslr(cmd.value).handle_error()?;
+                ops.flash_erase(addr, length).handle_error()?;
                 HandlerStatus::NeedsOK
             }
-            // --------- Environment --------- //
-            ext::ExtendedMode::QEnvironmentHexEncoded(cmd) if ops.configure_env().is_some() => {
-                let ops = ops.configure_env().unwrap();
-                ops.set_env(cmd.key, cmd.value).handle_error()?;
-                HandlerStatus::NeedsOK
-            }
-            ext::ExtendedMode::QEnvironmentUnset(cmd) if ops.configure_env().is_some() => {
-                let ops = ops.configure_env().unwrap();
-                ops.remove_env(cmd.key).handle_error()?;
-                HandlerStatus::NeedsOK
-            }
-            ext::ExtendedMode::QEnvironmentReset(_cmd) if ops.configure_env().is_some() => {
-                let ops = ops.configure_env().unwrap();
-                ops.reset_env().handle_error()?;
-                HandlerStatus::NeedsOK
-            }
-            // --------- Working Dir --------- //
-            ext::ExtendedMode::QSetWorkingDir(cmd) if ops.configure_working_dir().is_some() => {
-                let ops = ops.configure_working_dir().unwrap();
-                ops.cfg_working_dir(cmd.dir).handle_error()?;
-                HandlerStatus::NeedsOK
+            ext::Flash::vFlashWrite(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                match ops.flash_write(addr, cmd.val) {
+                    Ok(()) => HandlerStatus::NeedsOK,
+                    Err(FlashWriteError::NotFlash) => {
+                        res.write_str("E.memtype")?;
+                        HandlerStatus::Handled
+                    }
+                    Err(FlashWriteError::Target(e)) => return Err(e).handle_error(),
+                }
             }
-            // --------- Startup Shell --------- //
-            ext::ExtendedMode::QStartupWithShell(cmd)
-                if ops.configure_startup_shell().is_some() =>
-            {
-                let ops = ops.configure_startup_shell().unwrap();
-                ops.cfg_startup_with_shell(cmd.value).handle_error()?;
+            ext::Flash::vFlashDone(_) => {
+                ops.flash_done().handle_error()?;
                 HandlerStatus::NeedsOK
             }
-            _ => HandlerStatus::Handled,
         };
-
         Ok(handler_status)
     }
 
-    fn do_vcont(
+    fn handle_libraries<'a>(
         &mut self,
         res: &mut ResponseWriter<C>,
         target: &mut T,
-        actions: &mut dyn Iterator<Item = (TidSelector, ResumeAction)>,
-    ) -> Result<Option<DisconnectReason>, Error<T::Error, C::Error>> {
-        let mut err = Ok(());
+        command: ext::Libraries<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::target::ext::libraries::LibraryAddrs;
 
-        let mut check_gdb_interrupt = || match res.as_conn().peek() {
-            Ok(Some(0x03)) => true, // 0x03 is the interrupt byte
-            Ok(Some(_)) => false,   // it's nothing that can't wait...
-            Ok(None) => false,
-            Err(e) => {
-                err = Err(Error::ConnectionRead(e));
-                true // break ASAP if a connection error occurred
+        let ops = match target.libraries() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        let handler_status = match command {
+            ext::Libraries::qXferLibrariesRead(cmd) => {
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
+
+                xml.write_str("<library-list>");
+                ops.list_libraries(&mut |lib| {
+                    xml.write_str("<library name=\"");
+                    xml.write_xml_escaped(lib.name);
+                    xml.write_str("\">");
+                    let (tag, addrs) = match lib.addrs {
+                        LibraryAddrs::Segments(addrs) => ("<segment address=\"", addrs),
+                        LibraryAddrs::Sections(addrs) => ("<section address=\"", addrs),
+                    };
+                    for &addr in addrs {
+                        xml.write_str(tag);
+                        xml.write_hex(addr);
+                        xml.write_str("\"/>");
+                    }
+                    xml.write_str("</library>");
+                })
+                .handle_error()?;
+                xml.write_str("</library-list>");
+
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
             }
         };
+        Ok(handler_status)
+    }
 
-        let stop_reason = match target.base_ops() {
-            BaseOps::SingleThread(ops) => ops
-                .resume(
-                    // TODO?: add a more descriptive error if vcont has multiple threads in
-                    // single-threaded mode?
-                    actions.next().ok_or(Error::PacketUnexpected)?.1,
-                    &mut check_gdb_interrupt,
-                )
-                .map_err(Error::TargetError)?
-                .into(),
-            BaseOps::MultiThread(ops) => ops
-                .resume(Actions::new(actions), &mut check_gdb_interrupt)
-                .map_err(Error::TargetError)?,
+    fn handle_libraries_svr4<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::LibrariesSvr4<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use num_traits::NumCast;
+
+        let ops = match target.libraries_svr4() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
         };
 
-        err?;
+        let handler_status = match command {
+            ext::LibrariesSvr4::qXferLibrariesSvr4Read(cmd) => {
+                let start = match cmd.start {
+                    Some(start) => Some(NumCast::from(start).ok_or(Error::TargetMismatch)?),
+                    None => None,
+                };
+                let prev = match cmd.prev {
+                    Some(prev) => Some(NumCast::from(prev).ok_or(Error::TargetMismatch)?),
+                    None => None,
+                };
+
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
 
-        self.finish_vcont(stop_reason, res)
+                xml.write_str("<library-list-svr4 version=\"1.0\"");
+                if let Some(main_lm) = ops.main_lm().handle_error()? {
+                    xml.write_str(" main-lm=\"");
+                    xml.write_hex(main_lm);
+                    xml.write_str("\"");
+                }
+                xml.write_str(">");
+                ops.list_libraries_svr4(start, prev, &mut |lib| {
+                    xml.write_str("<library name=\"");
+                    xml.write_xml_escaped(lib.name);
+                    for &(attr, val) in &[
+                        ("\" lm=\"", lib.lm),
+                        ("\" l_addr=\"", lib.l_addr),
+                        ("\" l_ld=\"", lib.l_ld),
+                    ] {
+                        xml.write_str(attr);
+                        xml.write_hex(val);
+                    }
+                    xml.write_str("\"/>");
+                })
+                .handle_error()?;
+                xml.write_str("</library-list-svr4>");
+
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
+            }
+        };
+        Ok(handler_status)
     }
 
-    // DEVNOTE: `do_vcont` and `finish_vcont` could be merged into a single
-    // function, at the expense of slightly larger code. In the future, if the
-    // `vCont` machinery is re-written, there's no reason why the two functions
-    // couldn't be re-merged.
+    fn handle_auxv(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::Auxv,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.auxv() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
 
-    fn finish_vcont(
+        let handler_status = match command {
+            ext::Auxv::qXferAuxvRead(cmd) => {
+                let auxv = ops.get_auxv().handle_error()?;
+                write_xfer_chunk(res, auxv, cmd.offset, cmd.len)?;
+                HandlerStatus::Handled
+            }
+        };
+        Ok(handler_status)
+    }
+
+    fn handle_exec_file(
         &mut self,
-        stop_reason: ThreadStopReason<<T::Arch as Arch>::Usize>,
         res: &mut ResponseWriter<C>,
-    ) -> Result<Option<DisconnectReason>, Error<T::Error, C::Error>> {
-        match stop_reason {
-            ThreadStopReason::DoneStep | ThreadStopReason::GdbInterrupt => {
-                res.write_str("S05")?;
-                Ok(None)
+        target: &mut T,
+        command: ext::ExecFile,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.exec_file() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        let handler_status = match command {
+            ext::ExecFile::qXferExecFileRead(cmd) => {
+                // when no pid is specified, GDB is asking about the current process
+                let pid = cmd.pid.unwrap_or(self.current_mem_pid);
+                let filename = ops.get_exec_file(pid).handle_error()?;
+                write_xfer_chunk(res, filename, cmd.offset, cmd.len)?;
+                HandlerStatus::Handled
             }
-            ThreadStopReason::Signal(code) => {
-                res.write_str("S")?;
-                res.write_num(code)?;
-                Ok(None)
+        };
+        Ok(handler_status)
+    }
+
+    fn handle_sig_info<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::SigInfo<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.sig_info() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        // signal information is always associated with the current thread
+        let (pid, tid) = (self.current_mem_pid, self.current_mem_tid);
+
+        let handler_status = match command {
+            ext::SigInfo::qXferSigInfoRead(cmd) => {
+                let siginfo = ops.get_siginfo(pid, tid).handle_error()?;
+                write_xfer_chunk(res, siginfo, cmd.offset, cmd.len)?;
+                HandlerStatus::Handled
             }
-            ThreadStopReason::Halted => {
-                res.write_str("W19")?; // SIGSTOP
-                Ok(Some(DisconnectReason::TargetHalted))
+            ext::SigInfo::qXferSigInfoWrite(cmd) => {
+                if let Some(ops) = ops.support_write() {
+                    let n = ops
+                        .write_siginfo(pid, tid, cmd.offset, cmd.data)
+                        .handle_error()?;
+                    res.write_num(n)?;
+                }
+                HandlerStatus::Handled
             }
-            ThreadStopReason::SwBreak(tid)
-            | ThreadStopReason::HwBreak(tid)
-            | ThreadStopReason::Watch { tid, .. } => {
-                self.current_mem_tid = tid;
-                self.current_resume_tid = TidSelector::WithID(tid);
+        };
+        Ok(handler_status)
+    }
 
-                res.write_str("T05")?;
+    fn handle_os_data<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::OsData<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::target::ext::os_data::OsDataValue;
 
-                res.write_str("thread:")?;
-                res.write_thread_id(ThreadId {
-                    pid: Some(IdKind::WithID(FAKE_PID)),
-                    tid: IdKind::WithID(tid),
-                })?;
-                res.write_str(";")?;
+        let ops = match target.os_data() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
 
-                match stop_reason {
-                    // don't include addr on sw/hw break
-                    ThreadStopReason::SwBreak(_) => res.write_str("swbreak:")?,
-                    ThreadStopReason::HwBreak(_) => res.write_str("hwbreak:")?,
-                    ThreadStopReason::Watch { kind, addr, .. } => {
-                        use crate::target::ext::breakpoints::WatchKind;
-                        match kind {
-                            WatchKind::Write => res.write_str("watch:")?,
-                            WatchKind::Read => res.write_str("rwatch:")?,
-                            WatchKind::ReadWrite => res.write_str("awatch:")?,
+        let handler_status = match command {
+            ext::OsData::qXferOsDataRead(cmd) => {
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
+
+                if cmd.annex.is_empty() {
+                    // list the available tables
+                    xml.write_str("<osdata type=\"types\">");
+                    ops.list_osdata_types(&mut |kind| {
+                        xml.write_str("<item>");
+                        for &(column, value) in &[
+                            ("Type", kind.name),
+                            ("Description", kind.description),
+                            ("Title", kind.title),
+                        ] {
+                            xml.write_str("<column name=\"");
+                            xml.write_str(column);
+                            xml.write_str("\">");
+                            xml.write_xml_escaped(value.as_bytes());
+                            xml.write_str("</column>");
                         }
-                        res.write_num(addr)?;
-                    }
-                    _ => unreachable!(),
-                };
+                        xml.write_str("</item>");
+                    })
+                    .handle_error()?;
+                } else {
+                    xml.write_str("<osdata type=\"");
+                    xml.write_xml_escaped(cmd.annex);
+                    xml.write_str("\">");
+                    ops.list_osdata(cmd.annex, &mut |row| {
+                        xml.write_str("<item>");
+                        for column in row {
+                            xml.write_str("<column name=\"");
+                            xml.write_xml_escaped(column.name.as_bytes());
+                            xml.write_str("\">");
+                            match column.value {
+                                OsDataValue::Str(s) => xml.write_xml_escaped(s),
+                                OsDataValue::Int(n) => xml.write_dec(n),
+                            }
+                            xml.write_str("</column>");
+                        }
+                        xml.write_str("</item>");
+                    })
+                    .handle_error()?;
+                }
+                xml.write_str("</osdata>");
 
-                res.write_str(";")?;
-                Ok(None)
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
             }
-        }
+        };
+        Ok(handler_status)
     }
-}
 
-use crate::target::ext::base::singlethread::StopReason;
-impl<U> From<StopReason<U>> for ThreadStopReason<U> {
-    fn from(st_stop_reason: StopReason<U>) -> ThreadStopReason<U> {
-        match st_stop_reason {
-            StopReason::DoneStep => ThreadStopReason::DoneStep,
-            StopReason::GdbInterrupt => ThreadStopReason::GdbInterrupt,
-            StopReason::Halted => ThreadStopReason::Halted,
-            StopReason::SwBreak => ThreadStopReason::SwBreak(SINGLE_THREAD_TID),
-            StopReason::HwBreak => ThreadStopReason::HwBreak(SINGLE_THREAD_TID),
+    fn handle_branch_trace<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::BranchTrace<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::protocol::commands::_Qbtrace::Qbtrace;
+        use crate::target::ext::branch_trace::BtraceReadType;
+
+        let ops = match target.branch_trace() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        // branch tracing is always controlled on a per-thread basis
+        let (pid, tid) = (self.current_mem_pid, self.current_mem_tid);
+
+        let handler_status = match command {
+            ext::BranchTrace::Qbtrace(cmd) => match cmd {
+                Qbtrace::Bts => {
+                    ops.enable_btrace(pid, tid).handle_error()?;
+                    HandlerStatus::NeedsOK
+                }
+                Qbtrace::Off => {
+                    ops.disable_btrace(pid, tid).handle_error()?;
+                    HandlerStatus::NeedsOK
+                }
+                // not advertised in "qSupported"
+                Qbtrace::Pt => HandlerStatus::Handled,
+            },
+            ext::BranchTrace::qXferBtraceConfRead(cmd) => {
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
+
+                xml.write_str("<btrace-conf version=\"1.0\">");
+                if let Some(config) = ops.btrace_config(pid, tid).handle_error()? {
+                    xml.write_str("<bts");
+                    if let Some(size) = config.size {
+                        xml.write_str(" size=\"");
+                        xml.write_hex(size);
+                        xml.write_str("\"");
+                    }
+                    xml.write_str("/>");
+                }
+                xml.write_str("</btrace-conf>");
+
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
+            }
+            ext::BranchTrace::qXferBtraceRead(cmd) => {
+                let read_type = match cmd.annex {
+                    b"all" => BtraceReadType::All,
+                    b"new" => BtraceReadType::New,
+                    b"delta" => BtraceReadType::Delta,
+                    _ => return Err(Error::NonFatalError(1)),
+                };
+
+                // subsequent chunks are served from the same snapshot
+                if cmd.offset == 0 {
+                    ops.capture_btrace(pid, tid, read_type).handle_error()?;
+                }
+
+                let mut xml = XferWriter::new(cmd.buf, cmd.offset, cmd.len);
+
+                xml.write_str("<btrace version=\"1.0\">");
+                ops.read_btrace(pid, tid, &mut |begin, end| {
+                    xml.write_str("<block begin=\"");
+                    xml.write_hex(begin);
+                    xml.write_str("\" end=\"");
+                    xml.write_hex(end);
+                    xml.write_str("\"/>");
+                })
+                .handle_error()?;
+                xml.write_str("</btrace>");
+
+                write_xfer_window(res, xml.finish())?;
+                HandlerStatus::Handled
+            }
+        };
+        Ok(handler_status)
+    }
+
+    fn handle_file_io(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::FileIo,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::target::ext::file_io::SyscallResult;
+        use crate::target::ext::host_io::HostIoErrno;
+
+        let ops = match target.file_io() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        let handler_status = match command {
+            ext::FileIo::F(cmd) => {
+                // `F` packets are only ever sent in reply to a syscall request
+                let actions = self.syscall_resume.take().ok_or(Error::PacketUnexpected)?;
+
+                ops.complete_syscall(SyscallResult {
+                    retcode: cmd.retcode,
+                    errno: cmd.errno.map(HostIoErrno::from_raw),
+                    interrupted: cmd.ctrl_c,
+                })
+                .handle_error()?;
+
+                let disconnect = if cmd.ctrl_c {
+                    // the user interrupted the syscall, so instead of resuming the target, report
+                    // it as having stopped with SIGINT.
+                    let stop_reason = ProcessStopReason::SignalWithThread {
+                        pid: self.current_mem_pid,
+                        tid: self.current_mem_tid,
+                        signal: 2,
+                    };
+                    self.finish_vcont(target, stop_reason, res)?
+                } else {
+                    self.do_vcont(res, target, &mut actions.iter())?
+                };
+
+                match disconnect {
+                    Some(dc) => HandlerStatus::Disconnect(dc),
+                    None => HandlerStatus::Handled,
+                }
+            }
+        };
+        Ok(handler_status)
+    }
+
+    fn handle_host_io<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::HostIo<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::target::ext::host_io::{
+            FsKind, HostIoError, HostIoOpenFlags, HostIoOpenMode, HostIoStat,
+        };
+
+        let ops = match target.host_io() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        // Host I/O errors are reported as `F-1,errno`, instead of the usual `Exx`
+        macro_rules! handle_hostio_result {
+            ($ret:expr, |$val:pat| $callback:block) => {
+                match $ret {
+                    Ok($val) => $callback,
+                    Err(HostIoError::Errno(errno)) => {
+                        res.write_str("F-1,")?;
+                        res.write_num(errno as u32)?;
+                    }
+                    Err(HostIoError::Fatal(e)) => return Err(Error::TargetError(e)),
+                }
+            };
+        }
+
+        match command {
+            ext::HostIo::vFileOpen(cmd) => {
+                if let Some(ops) = ops.support_open() {
+                    let flags = HostIoOpenFlags(cmd.flags);
+                    let mode = HostIoOpenMode(cmd.mode);
+                    handle_hostio_result!(ops.open(cmd.filename, flags, mode), |fd| {
+                        res.write_str("F")?;
+                        res.write_num(fd)?;
+                    });
+                }
+            }
+            ext::HostIo::vFileClose(cmd) => {
+                if let Some(ops) = ops.support_close() {
+                    handle_hostio_result!(ops.close(cmd.fd), |_| {
+                        res.write_str("F0")?;
+                    });
+                }
+            }
+            ext::HostIo::vFilePread(cmd) => {
+                if let Some(ops) = ops.support_pread() {
+                    let count = cmd.count.min(cmd.buf.len());
+                    let buf = &mut cmd.buf[..count];
+                    handle_hostio_result!(ops.pread(cmd.fd, count, cmd.offset, buf), |n| {
+                        let n = n.min(count);
+                        res.write_str("F")?;
+                        res.write_num(n)?;
+                        res.write_str(";")?;
+                        res.write_binary(&buf[..n])?;
+                    });
+                }
+            }
+            ext::HostIo::vFilePwrite(cmd) => {
+                if let Some(ops) = ops.support_pwrite() {
+                    handle_hostio_result!(ops.pwrite(cmd.fd, cmd.offset, cmd.data), |n| {
+                        res.write_str("F")?;
+                        res.write_num(n)?;
+                    });
+                }
+            }
+            ext::HostIo::vFileFstat(cmd) => {
+                if let Some(ops) = ops.support_fstat() {
+                    handle_hostio_result!(ops.fstat(cmd.fd), |stat| {
+                        res.write_str("F")?;
+                        res.write_num(HostIoStat::SIZE)?;
+                        res.write_str(";")?;
+                        res.write_binary(&stat.to_be_bytes())?;
+                    });
+                }
+            }
+            ext::HostIo::vFileUnlink(cmd) => {
+                if let Some(ops) = ops.support_unlink() {
+                    handle_hostio_result!(ops.unlink(cmd.filename), |_| {
+                        res.write_str("F0")?;
+                    });
+                }
+            }
+            ext::HostIo::vFileReadlink(cmd) => {
+                if let Some(ops) = ops.support_readlink() {
+                    handle_hostio_result!(ops.readlink(cmd.filename, cmd.buf), |n| {
+                        let n = n.min(cmd.buf.len());
+                        res.write_str("F")?;
+                        res.write_num(n)?;
+                        res.write_str(";")?;
+                        res.write_binary(&cmd.buf[..n])?;
+                    });
+                }
+            }
+            ext::HostIo::vFileSetfs(cmd) => {
+                if let Some(ops) = ops.support_setfs() {
+                    let fs = match cmd.pid {
+                        None => FsKind::Stub,
+                        Some(pid) => FsKind::Pid(pid),
+                    };
+                    handle_hostio_result!(ops.setfs(fs), |_| {
+                        res.write_str("F0")?;
+                    });
+                }
+            }
+        };
+
+        Ok(HandlerStatus::Handled)
+    }
+
+    fn handle_tracepoints<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::Tracepoints<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        use crate::protocol::commands::_QTFrame::QTFrame;
+        use crate::protocol::commands::_QTDP::{TdpAction, QTDP};
+        use crate::target::ext::tracepoints::{
+            ExperimentState, FrameRequest, NewTracepoint, Tracepoint, TracepointAction,
+        };
+
+        let ops = match target.tracepoints() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        let handler_status = match command {
+            ext::Tracepoints::QTinit(_) => {
+                ops.tracepoints_init().handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::Tracepoints::QTDP(QTDP::Create(cmd)) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                self.tdp_while_stepping = false;
+                ops.tracepoint_create(NewTracepoint {
+                    number: Tracepoint(cmd.number),
+                    addr,
+                    enabled: cmd.enable,
+                    step_count: cmd.step,
+                    pass_count: cmd.pass,
+                    condition: cmd.condition,
+                })
+                .handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::Tracepoints::QTDP(QTDP::Extend(cmd)) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                self.tdp_while_stepping |= cmd.while_stepping;
+                for action in cmd.actions.into_iter() {
+                    let action = match action.ok_or(Error::PacketParse(
+                        crate::protocol::PacketParseError::MalformedCommand,
+                    ))? {
+                        TdpAction::Registers(mask) => TracepointAction::Registers { mask },
+                        TdpAction::Memory {
+                            basereg,
+                            offset,
+                            len,
+                        } => TracepointAction::Memory {
+                            // GDB uses a base register of `-1` to indicate an absolute address
+                            basereg: match basereg {
+                                0xffff_ffff => None,
+                                basereg => Some(basereg as u64),
+                            },
+                            offset,
+                            length: len,
+                        },
+                        TdpAction::Expression(expr) => TracepointAction::Expression { expr },
+                    };
+
+                    ops.tracepoint_add_action(
+                        Tracepoint(cmd.number),
+                        addr,
+                        self.tdp_while_stepping,
+                        action,
+                    )
+                    .handle_error()?;
+                }
+                HandlerStatus::NeedsOK
+            }
+            ext::Tracepoints::QTStart(_) => {
+                ops.trace_experiment_start().handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::Tracepoints::QTStop(_) => {
+                ops.trace_experiment_stop().handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::Tracepoints::qTStatus(_) => {
+                let status = ops.trace_experiment_status().handle_error()?;
+
+                match status.state {
+                    ExperimentState::Running => res.write_str("T1")?,
+                    ExperimentState::NotRun => res.write_str("T0;tnotrun:0")?,
+                    ExperimentState::Stopped => res.write_str("T0;tstop:0")?,
+                    ExperimentState::BufferFull => res.write_str("T0;tfull:0")?,
+                    ExperimentState::PassCount(tp) => {
+                        res.write_str("T0;tpasscount:")?;
+                        res.write_num(tp.0)?;
+                    }
+                }
+
+                res.write_str(";tframes:")?;
+                res.write_num(status.frames)?;
+                res.write_str(";tcreated:")?;
+                res.write_num(status.created)?;
+                HandlerStatus::Handled
+            }
+            ext::Tracepoints::QTFrame(cmd) => {
+                let from_be_bytes = |addr| {
+                    <T::Arch as Arch>::Usize::from_be_bytes(addr).ok_or(Error::TargetMismatch)
+                };
+
+                let frame = match cmd {
+                    QTFrame::Number(n) => FrameRequest::Number(n as u64),
+                    QTFrame::Pc(addr) => FrameRequest::AtPc(from_be_bytes(addr)?),
+                    QTFrame::Tdp(tp) => FrameRequest::Hit(Tracepoint(tp)),
+                    QTFrame::Range(start, end) => {
+                        FrameRequest::Between(from_be_bytes(start)?, from_be_bytes(end)?)
+                    }
+                    QTFrame::Outside(start, end) => {
+                        FrameRequest::Outside(from_be_bytes(start)?, from_be_bytes(end)?)
+                    }
+                    QTFrame::Exit => {
+                        ops.select_frame(FrameRequest::Exit).handle_error()?;
+                        return Ok(HandlerStatus::NeedsOK);
+                    }
+                };
+
+                match ops.select_frame(frame).handle_error()? {
+                    Some((frame, tp)) => {
+                        res.write_str("F")?;
+                        res.write_num(frame)?;
+                        res.write_str("T")?;
+                        res.write_num(tp.0)?;
+                    }
+                    None => res.write_str("F-1")?,
+                }
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+
+    fn handle_extended_mode<'a>(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        command: ext::ExtendedMode<'a>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.extended_mode() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        let handler_status = match command {
+            ext::ExtendedMode::ExclamationMark(_cmd) => {
+                ops.on_start().map_err(Error::TargetError)?;
+                HandlerStatus::NeedsOK
+            }
+            ext::ExtendedMode::R(_cmd) => {
+                ops.restart().map_err(Error::TargetError)?;
+                HandlerStatus::Handled
+            }
+            ext::ExtendedMode::vAttach(cmd) => {
+                ops.attach(cmd.pid).handle_error()?;
+
+                let pid = Self::normalize_pid(target, cmd.pid);
+
+                #[cfg(feature = "alloc")]
+                self.attached_pids.insert(pid, true);
+
+                self.report_initial_stop(res, target, pid)?
+            }
+            ext::ExtendedMode::vRun(cmd) => {
+                use crate::target::ext::extended_mode::Args;
+
+                let pid = ops
+                    .run(cmd.filename, Args::new(&mut cmd.args.into_iter()))
+                    .handle_error()?;
+
+                let pid = Self::normalize_pid(target, pid);
+
+                #[cfg(feature = "alloc")]
+                self.attached_pids.insert(pid, false);
+
+                self.report_initial_stop(res, target, pid)?
+            }
+            // --------- ASLR --------- //
+            ext::ExtendedMode::QDisableRandomization(cmd) if ops.configure_aslr().is_some() => {
+                let ops = ops.configure_aslr().unwrap();
+                ops.cfg_aslr(cmd.value).handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            // --------- Environment --------- //
+            ext::ExtendedMode::QEnvironmentHexEncoded(cmd) if ops.configure_env().is_some() => {
+                let ops = ops.configure_env().unwrap();
+                ops.set_env(cmd.key, cmd.value).handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::ExtendedMode::QEnvironmentUnset(cmd) if ops.configure_env().is_some() => {
+                let ops = ops.configure_env().unwrap();
+                ops.remove_env(cmd.key).handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            ext::ExtendedMode::QEnvironmentReset(_cmd) if ops.configure_env().is_some() => {
+                let ops = ops.configure_env().unwrap();
+                ops.reset_env().handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            // --------- Working Dir --------- //
+            ext::ExtendedMode::QSetWorkingDir(cmd) if ops.configure_working_dir().is_some() => {
+                let ops = ops.configure_working_dir().unwrap();
+                ops.cfg_working_dir(cmd.dir).handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            // --------- Startup Shell --------- //
+            ext::ExtendedMode::QStartupWithShell(cmd)
+                if ops.configure_startup_shell().is_some() =>
+            {
+                let ops = ops.configure_startup_shell().unwrap();
+                ops.cfg_startup_with_shell(cmd.value).handle_error()?;
+                HandlerStatus::NeedsOK
+            }
+            _ => HandlerStatus::Handled,
+        };
+
+        Ok(handler_status)
+    }
+
+    fn do_vcont(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        actions: &mut dyn Iterator<Item = (ThreadSelector, ResumeAction<<T::Arch as Arch>::Usize>)>,
+    ) -> Result<Option<DisconnectReason>, Error<T::Error, C::Error>> {
+        if self.non_stop {
+            Self::resume_non_stop(target, actions)?;
+            res.write_str("OK")?;
+            return Ok(None);
+        }
+
+        // keep track of the resume action when there's only a single one, so that the target
+        // can be transparently resumed after hitting a breakpoint whose condition is false.
+        let mut num_actions = 0;
+        let mut last_action = None;
+        let mut saved_actions = SyscallResume::new();
+        let mut saved_all_actions = true;
+        let mut actions = actions.inspect(|&action| {
+            saved_all_actions &= saved_actions.push(action);
+            num_actions += 1;
+            last_action = Some(action);
+        });
+
+        let mut stop_reason = self.resume(res, target, &mut actions)?;
+        while let Some(bp) = self.false_bp_cond(target, &stop_reason)? {
+            let (action, step_action) = match (num_actions, last_action) {
+                (1, Some((selector, ResumeAction::Continue)))
+                | (1, Some((selector, ResumeAction::ContinueWithSignal(_)))) => {
+                    ((selector, ResumeAction::Continue), ResumeAction::Step)
+                }
+                (1, Some((selector, ResumeAction::ReverseContinue)))
+                    if Self::supports_reverse_step(target) =>
+                {
+                    (
+                        (selector, ResumeAction::ReverseContinue),
+                        ResumeAction::ReverseStep,
+                    )
+                }
+                // the stop is reported as-is, and GDB will simply re-evaluate the condition
+                // itself.
+                _ => break,
+            };
+
+            // like gdbserver, the thread is stepped over the breakpoint (with the breakpoint
+            // lifted) before being resumed, as otherwise it'd immediately re-trigger.
+            let step = (
+                ThreadSelector::WithID {
+                    pid: bp.pid,
+                    tid: bp.tid,
+                },
+                step_action,
+            );
+            Self::set_breakpoint(target, bp.kind, bp.addr, false)?;
+            let step_res = self.resume(res, target, &mut core::iter::once(step));
+            Self::set_breakpoint(target, bp.kind, bp.addr, true)?;
+            stop_reason = step_res?;
+
+            // anything other than a completed step (e.g: hitting another breakpoint) is reported
+            // (or evaluated) as usual
+            if step_done(&stop_reason, bp.tid) {
+                stop_reason = self.resume(res, target, &mut core::iter::once(action))?;
+            }
+        }
+
+        if let ProcessStopReason::SyscallRequest { .. } = stop_reason {
+            // the target couldn't be resumed the same way once the syscall completes
+            if target.file_io().is_none() || !saved_all_actions {
+                return Err(Error::UnsupportedStopReason);
+            }
+            self.syscall_resume = Some(saved_actions);
+        }
+
+        self.finish_vcont(target, stop_reason, res)
+    }
+
+    /// In non-stop mode, resuming only updates the run-state of the selected
+    /// threads, with any resulting stop events being reported asynchronously.
+    #[allow(clippy::type_complexity)]
+    fn resume_non_stop(
+        target: &mut T,
+        actions: &mut dyn Iterator<Item = (ThreadSelector, ResumeAction<<T::Arch as Arch>::Usize>)>,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        let ops = match target.base_ops() {
+            BaseOps::MultiThread(ops) => ops.support_non_stop(),
+            BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => None,
+        }
+        .ok_or(Error::PacketUnexpected)?;
+
+        let mut actions = actions.map(|(selector, action)| (tid_selector(selector), action));
+        ops.resume_non_stop(Actions::new(&mut actions))
+            .map_err(Error::TargetError)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn resume(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        actions: &mut dyn Iterator<Item = (ThreadSelector, ResumeAction<<T::Arch as Arch>::Usize>)>,
+    ) -> Result<ProcessStopReason<<T::Arch as Arch>::Usize>, Error<T::Error, C::Error>> {
+        let mut err = Ok(());
+
+        let mut check_gdb_interrupt = || match res.as_conn().peek() {
+            Ok(Some(0x03)) => true, // 0x03 is the interrupt byte
+            Ok(Some(_)) => false,   // it's nothing that can't wait...
+            Ok(None) => false,
+            Err(e) => {
+                err = Err(Error::ConnectionRead(e));
+                true // break ASAP if a connection error occurred
+            }
+        };
+
+        let stop_reason = match target.base_ops() {
+            BaseOps::SingleThread(ops) => ThreadStopReason::from(
+                ops.resume(
+                    // TODO?: add a more descriptive error if vcont has multiple threads in
+                    // single-threaded mode?
+                    actions.next().ok_or(Error::PacketUnexpected)?.1,
+                    &mut check_gdb_interrupt,
+                )
+                .map_err(Error::TargetError)?,
+            )
+            .into(),
+            BaseOps::MultiThread(ops) => {
+                let mut actions =
+                    actions.map(|(selector, action)| (tid_selector(selector), action));
+                ops.resume(Actions::new(&mut actions), &mut check_gdb_interrupt)
+                    .map_err(Error::TargetError)?
+                    .into()
+            }
+            BaseOps::MultiProcess(ops) => ops
+                .resume(
+                    multiprocess::Actions::new(actions),
+                    &mut check_gdb_interrupt,
+                )
+                .map_err(Error::TargetError)?,
+        };
+
+        err?;
+
+        Ok(stop_reason)
+    }
+
+    fn supports_range_stepping(target: &mut T) -> bool {
+        match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.support_range_stepping().is_some(),
+            BaseOps::MultiThread(ops) => ops.support_range_stepping().is_some(),
+            BaseOps::MultiProcess(ops) => ops.support_range_stepping().is_some(),
+        }
+    }
+
+    fn supports_reverse_step(target: &mut T) -> bool {
+        match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.support_reverse_step().is_some(),
+            BaseOps::MultiThread(ops) => ops.support_reverse_step().is_some(),
+            BaseOps::MultiProcess(ops) => ops.support_reverse_step().is_some(),
+        }
+    }
+
+    fn supports_reverse_cont(target: &mut T) -> bool {
+        match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.support_reverse_cont().is_some(),
+            BaseOps::MultiThread(ops) => ops.support_reverse_cont().is_some(),
+            BaseOps::MultiProcess(ops) => ops.support_reverse_cont().is_some(),
+        }
+    }
+
+    fn supports_non_stop(target: &mut T) -> bool {
+        match target.base_ops() {
+            BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => false,
+            BaseOps::MultiThread(ops) => ops.support_non_stop().is_some(),
+        }
+    }
+
+    fn supports_threads_info(target: &mut T) -> bool {
+        match target.base_ops() {
+            BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => false,
+            BaseOps::MultiThread(ops) => ops.support_threads_info().is_some(),
+        }
+    }
+
+    /// Poll a target running in non-stop mode for the next thread stop event.
+    #[allow(clippy::type_complexity)]
+    fn poll_non_stop(
+        &mut self,
+        target: &mut T,
+        conn: &mut C,
+    ) -> Result<Option<ProcessStopReason<<T::Arch as Arch>::Usize>>, Error<T::Error, C::Error>>
+    {
+        loop {
+            let ops = match target.base_ops() {
+                BaseOps::MultiThread(ops) => ops.support_non_stop(),
+                BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => None,
+            };
+            let ops = match ops {
+                Some(ops) => ops,
+                None => return Ok(None),
+            };
+
+            let mut err = Ok(());
+
+            let mut check_gdb_incoming = || match conn.peek() {
+                Ok(Some(_)) => true,
+                Ok(None) => false,
+                Err(e) => {
+                    err = Err(Error::ConnectionRead(e));
+                    true // break ASAP if a connection error occurred
+                }
+            };
+
+            let stop_reason = ops
+                .poll_non_stop(&mut check_gdb_incoming)
+                .map_err(Error::TargetError)?;
+
+            err?;
+
+            let stop_reason = match stop_reason {
+                Some(stop_reason) => stop_reason.into(),
+                None => return Ok(None),
+            };
+
+            // GDB doesn't support File-I/O requests in non-stop mode, so the syscall is failed on
+            // GDB's behalf (with the requesting thread carrying on as before)
+            if let ProcessStopReason::SyscallRequest { .. } = stop_reason {
+                use crate::target::ext::file_io::SyscallResult;
+                use crate::target::ext::host_io::HostIoErrno;
+
+                let ops = target.file_io().ok_or(Error::UnsupportedStopReason)?;
+                ops.complete_syscall(SyscallResult {
+                    retcode: -1,
+                    errno: Some(HostIoErrno::EUNKNOWN),
+                    interrupted: false,
+                })
+                .handle_error()?;
+                continue;
+            }
+
+            // a thread which was stepping over a breakpoint can be resumed once its step completes
+            let (tid, action) = match self.finish_step_over(target, &stop_reason)? {
+                Some(tid) => (tid, ResumeAction::Continue),
+                None => match self.false_bp_cond(target, &stop_reason)? {
+                    None => return Ok(Some(stop_reason)),
+                    // like gdbserver, the thread is stepped over the breakpoint (with the
+                    // breakpoint lifted) before being resumed, as otherwise it'd immediately
+                    // re-trigger.
+                    Some(bp) => {
+                        self.start_step_over(target, bp)?;
+                        (bp.tid, ResumeAction::Step)
+                    }
+                },
+            };
+
+            if let BaseOps::MultiThread(ops) = target.base_ops() {
+                if let Some(ops) = ops.support_non_stop() {
+                    let mut actions = core::iter::once((TidSelector::WithID(tid), action));
+                    ops.resume_non_stop(Actions::new(&mut actions))
+                        .map_err(Error::TargetError)?;
+                }
+            }
+        }
+    }
+
+    /// Lift a breakpoint while a thread in non-stop mode steps over it.
+    #[cfg(feature = "alloc")]
+    fn start_step_over(
+        &mut self,
+        target: &mut T,
+        bp: StepOver<<T::Arch as Arch>::Usize>,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        // the breakpoint may already have been lifted for another thread
+        let lifted = (self.step_overs.values()).any(|o| (o.kind, o.addr) == (bp.kind, bp.addr));
+        if !lifted {
+            Self::set_breakpoint(target, bp.kind, bp.addr, false)?;
+        }
+        self.step_overs.insert(bp.tid, bp);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn start_step_over(
+        &mut self,
+        _target: &mut T,
+        _bp: StepOver<<T::Arch as Arch>::Usize>,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        Ok(())
+    }
+
+    /// Re-insert the breakpoint a thread in non-stop mode was stepping over,
+    /// returning the thread's TID if it completed its step (i.e: it should be
+    /// resumed).
+    #[cfg(feature = "alloc")]
+    fn finish_step_over(
+        &mut self,
+        target: &mut T,
+        stop_reason: &ProcessStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<Option<Tid>, Error<T::Error, C::Error>> {
+        let tid = match *stop_reason {
+            ProcessStopReason::SwBreak { tid, .. }
+            | ProcessStopReason::HwBreak { tid, .. }
+            | ProcessStopReason::Watch { tid, .. }
+            | ProcessStopReason::SignalWithThread { tid, .. } => tid,
+            _ => return Ok(None),
+        };
+
+        let bp = match self.step_overs.remove(&tid) {
+            Some(bp) => bp,
+            None => return Ok(None),
+        };
+
+        // only re-insert the breakpoint once no other thread is stepping over it (and GDB hasn't
+        // removed it in the meantime)
+        let lifted = (self.step_overs.values()).any(|o| (o.kind, o.addr) == (bp.kind, bp.addr));
+        if !lifted && self.bp_conds.contains_key(&(bp.kind, bp.addr)) {
+            Self::set_breakpoint(target, bp.kind, bp.addr, true)?;
+        }
+
+        Ok(if step_done(stop_reason, tid) {
+            Some(tid)
+        } else {
+            None
+        })
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn finish_step_over(
+        &mut self,
+        _target: &mut T,
+        _stop_reason: &ProcessStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<Option<Tid>, Error<T::Error, C::Error>> {
+        Ok(None)
+    }
+
+    /// Insert / remove a breakpoint of the given kind (i.e: `Z` packet type).
+    fn set_breakpoint(
+        target: &mut T,
+        kind: u8,
+        addr: <T::Arch as Arch>::Usize,
+        insert: bool,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        let res = match (kind, insert) {
+            (0, true) => (target.sw_breakpoint()).map(|op| op.add_sw_breakpoint(addr)),
+            (0, false) => (target.sw_breakpoint()).map(|op| op.remove_sw_breakpoint(addr)),
+            (1, true) => (target.hw_breakpoint()).map(|op| op.add_hw_breakpoint(addr)),
+            (1, false) => (target.hw_breakpoint()).map(|op| op.remove_hw_breakpoint(addr)),
+            _ => None,
+        };
+
+        if let Some(res) = res {
+            res.handle_error()?;
+        }
+        Ok(())
+    }
+
+    /// Check if the target stopped at a breakpoint with target-side conditions,
+    /// none of which evaluated to true (i.e: the stop shouldn't be reported),
+    /// returning the breakpoint which has to be stepped over.
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::type_complexity)]
+    fn false_bp_cond(
+        &mut self,
+        target: &mut T,
+        stop_reason: &ProcessStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<Option<StepOver<<T::Arch as Arch>::Usize>>, Error<T::Error, C::Error>> {
+        let (pid, tid, kind) = match *stop_reason {
+            ProcessStopReason::SwBreak { pid, tid } => (pid, tid, 0),
+            ProcessStopReason::HwBreak { pid, tid } => (pid, tid, 1),
+            _ => return Ok(None),
+        };
+
+        if self.bp_conds.is_empty() {
+            return Ok(None);
+        }
+
+        let mut regs: <T::Arch as Arch>::Registers = Default::default();
+        let res = match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.read_registers(&mut regs),
+            BaseOps::MultiThread(ops) => ops.read_registers(&mut regs, tid),
+            BaseOps::MultiProcess(ops) => ops.read_registers(&mut regs, pid, tid),
+        };
+        match res.handle_error() {
+            Ok(()) => {}
+            // if the PC can't be read, err on the side of reporting the stop
+            Err(Error::NonFatalError(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let addr = match <T::Arch as Arch>::pc(&regs) {
+            Some(addr) => addr,
+            None => return Ok(None),
+        };
+        let conds = match self.bp_conds.get(&(kind, addr)) {
+            Some(conds) => conds,
+            None => return Ok(None),
+        };
+
+        let mut ctx = agent::TargetContext::new(target, pid, tid);
+        let mut stack = [0; 100]; // same stack size as gdbserver
+        for cond in conds {
+            match agent::eval(&mut ctx, cond, &mut stack) {
+                Ok(0) => {}
+                Err(AgentError::TargetError(e)) => return Err(Error::TargetError(e)),
+                // like gdbserver, errors during evaluation are treated as the condition being true
+                Ok(_) | Err(_) => return Ok(None),
+            }
+        }
+
+        Ok(Some(StepOver {
+            pid,
+            tid,
+            kind,
+            addr,
+        }))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::type_complexity)]
+    fn false_bp_cond(
+        &mut self,
+        _target: &mut T,
+        _stop_reason: &ProcessStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<Option<StepOver<<T::Arch as Arch>::Usize>>, Error<T::Error, C::Error>> {
+        Ok(None)
+    }
+
+    /// On targets which don't implement the multiprocess API, the PID reported
+    /// by the target is ignored, and the FAKE_PID is used instead.
+    fn normalize_pid(target: &mut T, pid: Pid) -> Pid {
+        match target.base_ops() {
+            BaseOps::SingleThread(_) | BaseOps::MultiThread(_) => FAKE_PID,
+            BaseOps::MultiProcess(_) => pid,
+        }
+    }
+
+    /// Report which thread a newly spawned / attached process is stopped on.
+    fn report_initial_stop(
+        &mut self,
+        res: &mut ResponseWriter<C>,
+        target: &mut T,
+        pid: Pid,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let tid = Self::first_thread_in(target, pid)?;
+
+        // in non-stop mode, GDB waits for an asynchronous notification reporting that the new
+        // process has stopped
+        if self.non_stop {
+            self.vstopped_seq = VStoppedSeq::Pending(pid, tid.ok_or(Error::NoActiveThreads)?);
+            return Ok(HandlerStatus::NeedsOK);
+        }
+
+        match tid {
+            Some(tid) => {
+                let stop_reason = ProcessStopReason::SignalWithThread {
+                    pid,
+                    tid,
+                    signal: 5,
+                };
+                self.finish_vcont(target, stop_reason, res)?;
+            }
+            None => res.write_str("S05")?,
+        }
+        Ok(HandlerStatus::Handled)
+    }
+
+    /// Return the first active thread in process `pid`.
+    fn first_thread_in(target: &mut T, pid: Pid) -> Result<Option<Tid>, Error<T::Error, C::Error>> {
+        let mut tid = None;
+        Self::list_active_threads(target, &mut |active_pid, active_tid| {
+            if active_pid == pid && tid.is_none() {
+                tid = Some(active_tid);
+            }
+        })?;
+        Ok(tid)
+    }
+
+    /// Read memory from the thread most recently selected by GDB.
+    fn read_addrs(
+        &self,
+        target: &mut T,
+        addr: <T::Arch as Arch>::Usize,
+        data: &mut [u8],
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops.read_addrs(addr, data),
+            BaseOps::MultiThread(ops) => ops.read_addrs(addr, data, self.current_mem_tid),
+            BaseOps::MultiProcess(ops) => {
+                ops.read_addrs(addr, data, self.current_mem_pid, self.current_mem_tid)
+            }
+        }
+        .handle_error()
+    }
+
+    /// List all active threads as `(Pid, Tid)` pairs, regardless of which base
+    /// API the target implements.
+    fn list_active_threads(
+        target: &mut T,
+        thread_is_active: &mut dyn FnMut(Pid, Tid),
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        match target.base_ops() {
+            BaseOps::SingleThread(_) => thread_is_active(FAKE_PID, SINGLE_THREAD_TID),
+            BaseOps::MultiThread(ops) => ops
+                .list_active_threads(&mut |tid| thread_is_active(FAKE_PID, tid))
+                .map_err(Error::TargetError)?,
+            BaseOps::MultiProcess(ops) => ops
+                .list_active_threads(thread_is_active)
+                .map_err(Error::TargetError)?,
+        }
+        Ok(())
+    }
+
+    /// Return the active thread following `prev` (or the first active thread,
+    /// if `prev` is `None`).
+    #[allow(clippy::type_complexity)]
+    fn next_active_thread(
+        target: &mut T,
+        prev: Option<(Pid, Tid)>,
+    ) -> Result<Option<(Pid, Tid)>, Error<T::Error, C::Error>> {
+        let mut found_prev = prev.is_none();
+        let mut next = None;
+        Self::list_active_threads(target, &mut |pid, tid| {
+            if next.is_some() {
+                return;
+            }
+
+            if found_prev {
+                next = Some((pid, tid));
+            } else if Some((pid, tid)) == prev {
+                found_prev = true;
+            }
+        })?;
+
+        Ok(next)
+    }
+
+    /// Return the stopped thread following `prev` (or the first stopped
+    /// thread, if `prev` is `None`).
+    ///
+    /// Only used when running in non-stop mode.
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::type_complexity)]
+    fn next_stopped_thread(
+        &mut self,
+        target: &mut T,
+        prev: Option<(Pid, Tid)>,
+    ) -> Result<Option<(Pid, Tid)>, Error<T::Error, C::Error>> {
+        // all the stopped threads are collected up-front, using a single pass over the target's
+        // threads
+        if prev.is_none() {
+            let mut threads = Vec::new();
+            Self::list_active_threads(target, &mut |pid, tid| threads.push((pid, tid)))?;
+
+            self.stopped_threads.clear();
+            for (pid, tid) in threads.into_iter().rev() {
+                if Self::is_thread_stopped(target, tid)? {
+                    self.stopped_threads.push((pid, tid));
+                }
+            }
+        }
+
+        Ok(self.stopped_threads.pop())
+    }
+
+    /// Return the stopped thread following `prev` (or the first stopped
+    /// thread, if `prev` is `None`).
+    ///
+    /// Only used when running in non-stop mode.
+    #[cfg(not(feature = "alloc"))]
+    #[allow(clippy::type_complexity)]
+    fn next_stopped_thread(
+        &mut self,
+        target: &mut T,
+        mut prev: Option<(Pid, Tid)>,
+    ) -> Result<Option<(Pid, Tid)>, Error<T::Error, C::Error>> {
+        // without `alloc`, there's nowhere to store the list of stopped threads, so the target's
+        // threads are walked again to find the successor of `prev`
+        while let Some((pid, tid)) = Self::next_active_thread(target, prev)? {
+            if Self::is_thread_stopped(target, tid)? {
+                return Ok(Some((pid, tid)));
+            }
+            prev = Some((pid, tid));
+        }
+
+        Ok(None)
+    }
+
+    fn is_thread_stopped(target: &mut T, tid: Tid) -> Result<bool, Error<T::Error, C::Error>> {
+        let stopped = match target.base_ops() {
+            BaseOps::MultiThread(ops) => match ops.support_non_stop() {
+                Some(ops) => ops.is_thread_stopped(tid).map_err(Error::TargetError)?,
+                None => true,
+            },
+            BaseOps::SingleThread(_) | BaseOps::MultiProcess(_) => true,
+        };
+        Ok(stopped)
+    }
+
+    /// Stop referring to a process which has exited (or was killed), switching
+    /// to another active thread if the process was currently selected.
+    fn forget_process(
+        &mut self,
+        target: &mut T,
+        pid: Pid,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        if self.current_mem_pid == pid {
+            let mut next = None;
+            Self::list_active_threads(target, &mut |active_pid, active_tid| {
+                if active_pid != pid && next.is_none() {
+                    next = Some((active_pid, active_tid));
+                }
+            })?;
+
+            if let Some((next_pid, next_tid)) = next {
+                self.current_mem_pid = next_pid;
+                self.current_mem_tid = next_tid;
+            }
+        }
+
+        match self.current_resume_tid {
+            ThreadSelector::WithID {
+                pid: resume_pid, ..
+            }
+            | ThreadSelector::AllInProcess(resume_pid)
+                if resume_pid == pid =>
+            {
+                self.current_resume_tid = ThreadSelector::All
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // DEVNOTE: `do_vcont` and `finish_vcont` could be merged into a single
+    // function, at the expense of slightly larger code. In the future, if the
+    // `vCont` machinery is re-written, there's no reason why the two functions
+    // couldn't be re-merged.
+
+    fn finish_vcont(
+        &mut self,
+        target: &mut T,
+        stop_reason: ProcessStopReason<<T::Arch as Arch>::Usize>,
+        res: &mut ResponseWriter<C>,
+    ) -> Result<Option<DisconnectReason>, Error<T::Error, C::Error>> {
+        match stop_reason {
+            // in non-stop mode, every stop reply must specify which thread stopped
+            ProcessStopReason::DoneStep
+            | ProcessStopReason::GdbInterrupt
+            | ProcessStopReason::Signal(_)
+                if self.non_stop =>
+            {
+                Err(Error::UnsupportedStopReason)
+            }
+            ProcessStopReason::DoneStep | ProcessStopReason::GdbInterrupt => {
+                res.write_str("S05")?;
+                Ok(None)
+            }
+            ProcessStopReason::Signal(code) => {
+                res.write_str("S")?;
+                res.write_num(code)?;
+                Ok(None)
+            }
+            ProcessStopReason::Halted => {
+                res.write_str("W19")?; // SIGSTOP
+                Ok(Some(DisconnectReason::TargetHalted))
+            }
+            ProcessStopReason::SyscallRequest { pid, tid, syscall } => {
+                // there's no way to report a syscall request via a `%Stop` notification
+                if self.non_stop {
+                    return Err(Error::UnsupportedStopReason);
+                }
+
+                // GDB accesses the requesting thread's memory while performing the syscall
+                self.current_mem_pid = pid;
+                self.current_mem_tid = tid;
+                self.current_resume_tid = ThreadSelector::WithID { pid, tid };
+
+                res.write_str("F")?;
+                write_syscall(res, syscall)?;
+                Ok(None)
+            }
+            ProcessStopReason::Exited { pid, status } => {
+                self.forget_process(target, pid)?;
+
+                res.write_str("W")?;
+                res.write_num(status)?;
+                res.write_str(";process:")?;
+                res.write_num(pid.get())?;
+                Ok(None)
+            }
+            ProcessStopReason::SwBreak { pid, tid }
+            | ProcessStopReason::HwBreak { pid, tid }
+            | ProcessStopReason::Watch { pid, tid, .. }
+            | ProcessStopReason::SignalWithThread { pid, tid, .. }
+            | ProcessStopReason::ReplayLog { pid, tid, .. } => {
+                self.current_mem_pid = pid;
+                self.current_mem_tid = tid;
+                self.current_resume_tid = ThreadSelector::WithID { pid, tid };
+
+                res.write_str("T")?;
+                match stop_reason {
+                    ProcessStopReason::SignalWithThread { signal, .. } => res.write_num(signal)?,
+                    _ => res.write_str("05")?,
+                }
+
+                res.write_str("thread:")?;
+                res.write_thread_id(ThreadId {
+                    pid: Some(IdKind::WithID(pid)),
+                    tid: IdKind::WithID(tid),
+                })?;
+                res.write_str(";")?;
+
+                match stop_reason {
+                    // don't include addr on sw/hw break
+                    ProcessStopReason::SwBreak { .. } => res.write_str("swbreak:;")?,
+                    ProcessStopReason::HwBreak { .. } => res.write_str("hwbreak:;")?,
+                    ProcessStopReason::Watch { kind, addr, .. } => {
+                        use crate::target::ext::breakpoints::WatchKind;
+                        match kind {
+                            WatchKind::Write => res.write_str("watch:")?,
+                            WatchKind::Read => res.write_str("rwatch:")?,
+                            WatchKind::ReadWrite => res.write_str("awatch:")?,
+                        }
+                        res.write_num(addr)?;
+                        res.write_str(";")?;
+                    }
+                    ProcessStopReason::ReplayLog { pos, .. } => {
+                        use crate::target::ext::base::ReplayLogPosition;
+                        match pos {
+                            ReplayLogPosition::Begin => res.write_str("replaylog:begin;")?,
+                            ReplayLogPosition::End => res.write_str("replaylog:end;")?,
+                        }
+                    }
+                    // the signal number is the only stop-reason info
+                    ProcessStopReason::SignalWithThread { .. } => {}
+                    _ => unreachable!(),
+                };
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Write the requested chunk of `data` in response to a `qXfer:<object>:read`
+/// packet.
+fn write_xfer_chunk<C: Connection>(
+    res: &mut ResponseWriter<C>,
+    data: &[u8],
+    offset: usize,
+    len: usize,
+) -> Result<(), ResponseWriterError<C::Error>> {
+    if offset >= data.len() {
+        // no more data
+        res.write_str("l")
+    } else if offset + len >= data.len() {
+        // last little bit of data
+        res.write_str("l")?;
+        res.write_binary(&data[offset..])
+    } else {
+        // still more data
+        res.write_str("m")?;
+        res.write_binary(&data[offset..(offset + len)])
+    }
+}
+
+/// Write a window of data captured by an `XferWriter` in response to a
+/// `qXfer:<object>:read` packet.
+fn write_xfer_window<C: Connection>(
+    res: &mut ResponseWriter<C>,
+    (data, more): (&[u8], bool),
+) -> Result<(), ResponseWriterError<C::Error>> {
+    res.write_str(if more { "m" } else { "l" })?;
+    res.write_binary(data)
+}
+
+/// Write the body of a File-I/O syscall request (i.e: everything after the
+/// leading `F`).
+fn write_syscall<C: Connection, U: PrimInt + BeBytes>(
+    res: &mut ResponseWriter<C>,
+    syscall: Syscall<U>,
+) -> Result<(), ResponseWriterError<C::Error>> {
+    // strings are sent as `pointer/length` pairs
+    let write_str_ptr = |res: &mut ResponseWriter<C>, ptr: U, len: U| {
+        res.write_num(ptr)?;
+        res.write_str("/")?;
+        res.write_num(len)
+    };
+
+    match syscall {
+        Syscall::Open {
+            path,
+            path_len,
+            flags,
+            mode,
+        } => {
+            res.write_str("open,")?;
+            write_str_ptr(res, path, path_len)?;
+            res.write_str(",")?;
+            res.write_num(flags.0)?;
+            res.write_str(",")?;
+            res.write_num(mode.0)?;
+        }
+        Syscall::Close { fd } => {
+            res.write_str("close,")?;
+            res.write_num(fd)?;
+        }
+        Syscall::Read { fd, buf, count } | Syscall::Write { fd, buf, count } => {
+            match syscall {
+                Syscall::Read { .. } => res.write_str("read,")?,
+                _ => res.write_str("write,")?,
+            }
+            res.write_num(fd)?;
+            res.write_str(",")?;
+            res.write_num(buf)?;
+            res.write_str(",")?;
+            res.write_num(count)?;
+        }
+        Syscall::Lseek { fd, offset, whence } => {
+            res.write_str("lseek,")?;
+            res.write_num(fd)?;
+            res.write_str(",")?;
+            if offset < 0 {
+                res.write_str("-")?;
+            }
+            res.write_num(offset.wrapping_abs() as u64)?;
+            res.write_str(",")?;
+            res.write_num(whence as u8)?;
+        }
+        Syscall::Rename {
+            old_path,
+            old_path_len,
+            new_path,
+            new_path_len,
+        } => {
+            res.write_str("rename,")?;
+            write_str_ptr(res, old_path, old_path_len)?;
+            res.write_str(",")?;
+            write_str_ptr(res, new_path, new_path_len)?;
+        }
+        Syscall::Unlink { path, path_len } => {
+            res.write_str("unlink,")?;
+            write_str_ptr(res, path, path_len)?;
+        }
+        Syscall::Stat {
+            path,
+            path_len,
+            buf,
+        } => {
+            res.write_str("stat,")?;
+            write_str_ptr(res, path, path_len)?;
+            res.write_str(",")?;
+            res.write_num(buf)?;
+        }
+        Syscall::Fstat { fd, buf } => {
+            res.write_str("fstat,")?;
+            res.write_num(fd)?;
+            res.write_str(",")?;
+            res.write_num(buf)?;
+        }
+        Syscall::GetTimeOfDay { tv, tz } => {
+            res.write_str("gettimeofday,")?;
+            res.write_num(tv)?;
+            res.write_str(",")?;
+            res.write_num(tz)?;
+        }
+        Syscall::IsATty { fd } => {
+            res.write_str("isatty,")?;
+            res.write_num(fd)?;
+        }
+        Syscall::System {
+            command,
+            command_len,
+        } => {
+            res.write_str("system,")?;
+            write_str_ptr(res, command, command_len)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert a thread-id sent by GDB into a `ThreadSelector`.
+///
+/// Returns `None` if the thread-id doesn't correspond to a well-defined set of
+/// threads (e.g: "any" thread).
+fn thread_selector(thread: ThreadId) -> Option<ThreadSelector> {
+    let selector = match (thread.pid, thread.tid) {
+        (None, IdKind::WithID(tid)) => ThreadSelector::WithID { pid: FAKE_PID, tid },
+        (Some(IdKind::WithID(pid)), IdKind::WithID(tid)) => ThreadSelector::WithID { pid, tid },
+        (Some(IdKind::WithID(pid)), IdKind::All) => ThreadSelector::AllInProcess(pid),
+        (None, IdKind::All) | (Some(IdKind::All), IdKind::All) => ThreadSelector::All,
+        _ => return None,
+    };
+    Some(selector)
+}
+
+/// Check if a stop reason indicates that thread `tid` completed a single step.
+fn step_done<U>(stop_reason: &ProcessStopReason<U>, tid: Tid) -> bool {
+    match *stop_reason {
+        ProcessStopReason::DoneStep => true,
+        ProcessStopReason::SignalWithThread {
+            tid: stop_tid,
+            signal: 5,
+            ..
+        } => stop_tid == tid,
+        _ => false,
+    }
+}
+
+/// Convert a `ThreadSelector` into the `TidSelector` used by multi threaded
+/// targets (which only ever debug a single process).
+fn tid_selector(selector: ThreadSelector) -> TidSelector {
+    match selector {
+        ThreadSelector::WithID { tid, .. } => TidSelector::WithID(tid),
+        ThreadSelector::AllInProcess(_) | ThreadSelector::All => TidSelector::All,
+    }
+}
+
+use crate::target::ext::base::singlethread::StopReason;
+impl<U> From<StopReason<U>> for ThreadStopReason<U> {
+    fn from(st_stop_reason: StopReason<U>) -> ThreadStopReason<U> {
+        match st_stop_reason {
+            StopReason::DoneStep => ThreadStopReason::DoneStep,
+            StopReason::GdbInterrupt => ThreadStopReason::GdbInterrupt,
+            StopReason::Halted => ThreadStopReason::Halted,
+            StopReason::SwBreak => ThreadStopReason::SwBreak(SINGLE_THREAD_TID),
+            StopReason::HwBreak => ThreadStopReason::HwBreak(SINGLE_THREAD_TID),
             StopReason::Watch { kind, addr } => ThreadStopReason::Watch {
                 tid: SINGLE_THREAD_TID,
                 kind,
                 addr,
             },
             StopReason::Signal(sig) => ThreadStopReason::Signal(sig),
+            StopReason::SyscallRequest(syscall) => ThreadStopReason::SyscallRequest {
+                tid: SINGLE_THREAD_TID,
+                syscall,
+            },
+            StopReason::ReplayLog(pos) => ThreadStopReason::ReplayLog {
+                tid: SINGLE_THREAD_TID,
+                pos,
+            },
+        }
+    }
+}
+
+impl<U> From<ThreadStopReason<U>> for ProcessStopReason<U> {
+    fn from(mt_stop_reason: ThreadStopReason<U>) -> ProcessStopReason<U> {
+        let pid = FAKE_PID;
+        match mt_stop_reason {
+            ThreadStopReason::DoneStep => ProcessStopReason::DoneStep,
+            ThreadStopReason::GdbInterrupt => ProcessStopReason::GdbInterrupt,
+            ThreadStopReason::Halted => ProcessStopReason::Halted,
+            ThreadStopReason::SwBreak(tid) => ProcessStopReason::SwBreak { pid, tid },
+            ThreadStopReason::HwBreak(tid) => ProcessStopReason::HwBreak { pid, tid },
+            ThreadStopReason::Watch { tid, kind, addr } => ProcessStopReason::Watch {
+                pid,
+                tid,
+                kind,
+                addr,
+            },
+            ThreadStopReason::Signal(sig) => ProcessStopReason::Signal(sig),
+            ThreadStopReason::SignalWithThread { tid, signal } => {
+                ProcessStopReason::SignalWithThread { pid, tid, signal }
+            }
+            ThreadStopReason::SyscallRequest { tid, syscall } => {
+                ProcessStopReason::SyscallRequest { pid, tid, syscall }
+            }
+            ThreadStopReason::ReplayLog { tid, pos } => {
+                ProcessStopReason::ReplayLog { pid, tid, pos }
+            }
         }
     }
 }
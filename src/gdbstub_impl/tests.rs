@@ -0,0 +1,411 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::arch::arm::{reg::ArmCoreRegs, Armv4t};
+use crate::common::{Pid, Tid};
+use crate::connection::Connection;
+use crate::target::ext::base::multithread::{
+    self, MultiThreadNonStop, MultiThreadNonStopOps, MultiThreadOps, ThreadStopReason,
+};
+use crate::target::ext::base::singlethread::{
+    ResumeAction, SingleThreadOps, SingleThreadReverseCont, SingleThreadReverseContOps,
+    SingleThreadReverseStep, SingleThreadReverseStepOps, StopReason,
+};
+use crate::target::ext::base::BaseOps;
+use crate::target::ext::breakpoints::{SwBreakpoint, SwBreakpointOps};
+use crate::target::ext::extended_mode::{Args, ExtendedMode, ExtendedModeOps, ShouldTerminate};
+use crate::target::{Target, TargetResult};
+use crate::{DisconnectReason, GdbStub};
+
+/// A `Connection` which replays a fixed sequence of packets, recording
+/// everything written to it.
+struct MockConnection {
+    input: VecDeque<u8>,
+    output: Vec<u8>,
+}
+
+impl MockConnection {
+    fn new(packets: &[&str]) -> MockConnection {
+        let mut input = VecDeque::new();
+        for packet in packets {
+            let checksum = packet.bytes().fold(0u8, |sum, b| sum.wrapping_add(b));
+            let frame = format!("${}#{:02x}", packet, checksum);
+            input.extend(frame.bytes());
+        }
+
+        MockConnection {
+            input,
+            output: Vec::new(),
+        }
+    }
+}
+
+impl Connection for MockConnection {
+    type Error = &'static str;
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.input.pop_front().ok_or("out of input")
+    }
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        self.output.push(byte);
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        Ok(self.input.front().copied())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A fake CPU which executes a 4-byte `nop` at every address. Like real
+/// hardware, breakpoints trap _before_ the instruction at their address is
+/// executed.
+#[derive(Default)]
+struct MockTarget {
+    regs: ArmCoreRegs,
+    breakpoints: Vec<u32>,
+    continues: usize,
+}
+
+impl Target for MockTarget {
+    type Arch = Armv4t;
+    type Error = &'static str;
+
+    fn base_ops(&mut self) -> BaseOps<'_, Self::Arch, Self::Error> {
+        BaseOps::SingleThread(self)
+    }
+
+    fn sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl SingleThreadOps for MockTarget {
+    fn resume(
+        &mut self,
+        action: ResumeAction<u32>,
+        _check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<StopReason<u32>, Self::Error> {
+        match action {
+            ResumeAction::Step => {
+                self.regs.pc += 4;
+                Ok(StopReason::DoneStep)
+            }
+            ResumeAction::ReverseStep => {
+                self.regs.pc -= 4;
+                Ok(StopReason::DoneStep)
+            }
+            ResumeAction::Continue | ResumeAction::ReverseContinue => {
+                // bail out instead of spinning forever if the target keeps getting resumed
+                // without making progress
+                self.continues += 1;
+                if self.continues > 10 {
+                    return Ok(StopReason::Halted);
+                }
+
+                loop {
+                    if self.breakpoints.contains(&self.regs.pc) {
+                        return Ok(StopReason::SwBreak);
+                    }
+                    match action {
+                        ResumeAction::Continue => self.regs.pc += 4,
+                        _ => self.regs.pc -= 4,
+                    }
+                }
+            }
+            _ => Err("unexpected resume action"),
+        }
+    }
+
+    fn read_registers(&mut self, regs: &mut ArmCoreRegs) -> TargetResult<(), Self> {
+        *regs = self.regs.clone();
+        Ok(())
+    }
+
+    fn write_registers(&mut self, regs: &ArmCoreRegs) -> TargetResult<(), Self> {
+        self.regs = regs.clone();
+        Ok(())
+    }
+
+    fn read_addrs(&mut self, _start_addr: u32, data: &mut [u8]) -> TargetResult<(), Self> {
+        for b in data {
+            *b = 0;
+        }
+        Ok(())
+    }
+
+    fn write_addrs(&mut self, _start_addr: u32, _data: &[u8]) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn support_reverse_step(&mut self) -> Option<SingleThreadReverseStepOps<Self>> {
+        Some(self)
+    }
+
+    fn support_reverse_cont(&mut self) -> Option<SingleThreadReverseContOps<Self>> {
+        Some(self)
+    }
+}
+
+impl SingleThreadReverseStep for MockTarget {}
+
+impl SingleThreadReverseCont for MockTarget {}
+
+impl SwBreakpoint for MockTarget {
+    fn add_sw_breakpoint(&mut self, addr: u32) -> TargetResult<bool, Self> {
+        self.breakpoints.push(addr);
+        Ok(true)
+    }
+
+    fn remove_sw_breakpoint(&mut self, addr: u32) -> TargetResult<bool, Self> {
+        match self.breakpoints.iter().position(|x| *x == addr) {
+            None => return Ok(false),
+            Some(pos) => self.breakpoints.remove(pos),
+        };
+        Ok(true)
+    }
+}
+
+/// Run a debugging session to completion, returning everything `gdbstub`
+/// sent to GDB.
+fn run_session(target: &mut MockTarget, packets: &[&str]) -> String {
+    let mut conn = MockConnection::new(packets);
+    let reason = GdbStub::new(&mut conn as &mut dyn Connection<Error = _>)
+        .run(target)
+        .unwrap();
+    assert!(matches!(reason, DisconnectReason::Disconnect));
+    String::from_utf8(conn.output).unwrap()
+}
+
+#[test]
+fn false_bp_cond_steps_over_breakpoint() {
+    let mut target = MockTarget::default();
+
+    // `const8 0; end` is always false
+    let out = run_session(&mut target, &["Z0,1000,4;X3,220027", "Z0,1010,4", "c", "D"]);
+
+    // the false condition is silently stepped over, and the target stops at the
+    // following breakpoint
+    assert_eq!(target.regs.pc, 0x1010);
+    assert!(out.contains("swbreak"));
+    assert_eq!(target.breakpoints, [0x1010, 0x1000]);
+}
+
+#[test]
+fn false_bp_cond_reverse_steps_over_breakpoint() {
+    let mut target = MockTarget::default();
+    target.regs.pc = 0x2000;
+
+    // `const8 0; end` is always false
+    let out = run_session(
+        &mut target,
+        &["Z0,1010,4;X3,220027", "Z0,1000,4", "bc", "D"],
+    );
+
+    // when running in reverse, the false condition is stepped over backwards
+    assert_eq!(target.regs.pc, 0x1000);
+    assert!(out.contains("swbreak"));
+}
+
+#[test]
+fn bp_cond_reads_registers() {
+    let mut target = MockTarget::default();
+    target.regs.r[1] = 1;
+
+    // `reg 1; end` is true, since r1 is non-zero
+    let out = run_session(
+        &mut target,
+        &["Z0,1000,4;X4,26000127", "Z0,1010,4", "c", "D"],
+    );
+
+    assert_eq!(target.regs.pc, 0x1000);
+    assert!(out.contains("swbreak"));
+}
+
+#[test]
+fn x_binary_upload_prefix() {
+    let mut target = MockTarget::default();
+
+    let out = run_session(&mut target, &["qSupported:binary-upload+", "x1000,4", "D"]);
+
+    // GDB expects the data to be prefixed with a `b`
+    assert!(out.contains("$b\0\0\0\0#"));
+}
+
+#[test]
+fn x_raw_without_binary_upload() {
+    let mut target = MockTarget::default();
+
+    // LLDB doesn't negotiate `binary-upload`, and probes for `x` support with an empty read
+    let out = run_session(
+        &mut target,
+        &["qSupported:xmlRegisters=i386", "x0,0", "x1000,4", "D"],
+    );
+
+    assert!(out.contains("$OK#"));
+    assert!(out.contains("$\0\0\0\0#"));
+}
+
+/// A fake multi threaded target which runs in non-stop mode, and can spawn /
+/// attach to a (single threaded) process. The thread is never resumed.
+struct MockNonStopTarget;
+
+fn mock_tid() -> Tid {
+    Tid::new(1).unwrap()
+}
+
+impl Target for MockNonStopTarget {
+    type Arch = Armv4t;
+    type Error = &'static str;
+
+    fn base_ops(&mut self) -> BaseOps<'_, Self::Arch, Self::Error> {
+        BaseOps::MultiThread(self)
+    }
+
+    fn extended_mode(&mut self) -> Option<ExtendedModeOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl MultiThreadOps for MockNonStopTarget {
+    fn resume(
+        &mut self,
+        _actions: multithread::Actions<'_, u32>,
+        _check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<ThreadStopReason<u32>, Self::Error> {
+        Err("unexpected resume")
+    }
+
+    fn read_registers(&mut self, _regs: &mut ArmCoreRegs, _tid: Tid) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn write_registers(&mut self, _regs: &ArmCoreRegs, _tid: Tid) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn read_addrs(
+        &mut self,
+        _start_addr: u32,
+        _data: &mut [u8],
+        _tid: Tid,
+    ) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn write_addrs(&mut self, _start_addr: u32, _data: &[u8], _tid: Tid) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn list_active_threads(
+        &mut self,
+        thread_is_active: &mut dyn FnMut(Tid),
+    ) -> Result<(), Self::Error> {
+        thread_is_active(mock_tid());
+        Ok(())
+    }
+
+    fn support_non_stop(&mut self) -> Option<MultiThreadNonStopOps<Self>> {
+        Some(self)
+    }
+}
+
+impl MultiThreadNonStop for MockNonStopTarget {
+    fn resume_non_stop(
+        &mut self,
+        _actions: multithread::Actions<'_, u32>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn poll_non_stop(
+        &mut self,
+        _check_gdb_incoming: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<ThreadStopReason<u32>>, Self::Error> {
+        Ok(None)
+    }
+
+    fn is_thread_stopped(&mut self, _tid: Tid) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+impl ExtendedMode for MockNonStopTarget {
+    fn run(&mut self, _filename: Option<&[u8]>, _args: Args) -> TargetResult<Pid, Self> {
+        Ok(Pid::new(1).unwrap())
+    }
+
+    fn attach(&mut self, _pid: Pid) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn kill(&mut self, _pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+        Ok(ShouldTerminate::Yes)
+    }
+
+    fn restart(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn run_non_stop_session(packets: &[&str]) -> String {
+    let mut conn = MockConnection::new(packets);
+    let reason = GdbStub::new(&mut conn as &mut dyn Connection<Error = _>)
+        .run(&mut MockNonStopTarget)
+        .unwrap();
+    assert!(matches!(reason, DisconnectReason::Disconnect));
+    String::from_utf8(conn.output).unwrap()
+}
+
+#[test]
+fn non_stop_vattach_notifies_stop() {
+    let out = run_non_stop_session(&["QNonStop:1", "vAttach;1", "vStopped", "D"]);
+
+    // the attached process's stop is reported asynchronously, instead of as a reply
+    assert!(!out.contains("$T05"));
+    assert!(out.contains("%Stop:T00thread:p01.01;#"));
+}
+
+#[test]
+fn non_stop_vrun_notifies_stop() {
+    let out = run_non_stop_session(&["QNonStop:1", "vRun;", "vStopped", "D"]);
+
+    assert!(!out.contains("$T05"));
+    assert!(out.contains("%Stop:T00thread:p01.01;#"));
+}
+
+fn run_kill_session(packet: &str) -> String {
+    let mut conn = MockConnection::new(&[packet]);
+    let reason = GdbStub::new(&mut conn as &mut dyn Connection<Error = _>)
+        .run(&mut MockTarget::default())
+        .unwrap();
+    assert!(matches!(reason, DisconnectReason::Kill));
+    String::from_utf8(conn.output).unwrap()
+}
+
+#[test]
+fn vkill_replies_before_disconnecting() {
+    assert!(run_kill_session("vKill;1").contains("$OK#"));
+}
+
+#[test]
+fn plain_kill_has_no_reply() {
+    assert!(!run_kill_session("k").contains('$'));
+}
+
+#[test]
+fn resume_with_signal_rejects_addr() {
+    let mut target = MockTarget::default();
+
+    let out = run_session(&mut target, &["C05;1000", "S05;1000", "D"]);
+
+    // neither packet resumes the target
+    assert_eq!(out.matches("$E16#").count(), 2);
+    assert_eq!(target.regs.pc, 0);
+}
@@ -0,0 +1,140 @@
+use crate::internal::BeBytes;
+
+/// Serializes a `qXfer:<object>:read` object on-the-fly, only keeping the
+/// window of bytes requested by GDB.
+///
+/// This makes it possible to serve large, dynamically generated objects (e.g:
+/// XML documents listing a target's libraries) without having to buffer the
+/// entire object in memory.
+pub(super) struct XferWriter<'a> {
+    buf: &'a mut [u8],
+    offset: usize,
+    total: usize,
+}
+
+impl<'a> XferWriter<'a> {
+    /// Create a new `XferWriter` which captures up to `len` bytes of the
+    /// serialized object (starting at `offset`) into `buf`.
+    pub fn new(buf: &'a mut [u8], offset: usize, len: usize) -> XferWriter<'a> {
+        let len = len.min(buf.len());
+        XferWriter {
+            buf: &mut buf[..len],
+            offset,
+            total: 0,
+        }
+    }
+
+    /// Write raw bytes.
+    pub fn write(&mut self, data: &[u8]) {
+        for &b in data {
+            if let Some(i) = self.total.checked_sub(self.offset) {
+                if let Some(slot) = self.buf.get_mut(i) {
+                    *slot = b;
+                }
+            }
+            self.total += 1;
+        }
+    }
+
+    /// Write a string.
+    pub fn write_str(&mut self, s: &str) {
+        self.write(s.as_bytes())
+    }
+
+    /// Write a number as a `0x`-prefixed hex string.
+    pub fn write_hex<U: BeBytes>(&mut self, val: U) {
+        self.write_str("0x");
+        self.write_num(val)
+    }
+
+    /// Write a number as a (non-prefixed) hex string.
+    pub fn write_num<U: BeBytes>(&mut self, val: U) {
+        let mut buf = [0; 16];
+        // infallible (unless val is a >128 bit number)
+        let len = val.to_be_bytes(&mut buf).unwrap();
+
+        let mut leading_zero = true;
+        for b in &buf[..len] {
+            for &nibble in &[b >> 4, b & 0xf] {
+                leading_zero &= nibble == 0;
+                if !leading_zero {
+                    self.write(&[b"0123456789abcdef"[nibble as usize]]);
+                }
+            }
+        }
+        if leading_zero {
+            self.write_str("0");
+        }
+    }
+
+    /// Write a number as a decimal string.
+    pub fn write_dec(&mut self, mut val: u64) {
+        // max number of decimal digits in a 64 bit number
+        let mut buf = [0; 20];
+        let mut i = buf.len();
+        loop {
+            i -= 1;
+            buf[i] = b'0' + (val % 10) as u8;
+            val /= 10;
+            if val == 0 {
+                break;
+            }
+        }
+        self.write(&buf[i..])
+    }
+
+    /// Write text which is escaped for use in XML attributes / content.
+    pub fn write_xml_escaped(&mut self, text: &[u8]) {
+        for &b in text {
+            match b {
+                b'<' => self.write_str("&lt;"),
+                b'>' => self.write_str("&gt;"),
+                b'&' => self.write_str("&amp;"),
+                b'"' => self.write_str("&quot;"),
+                b'\'' => self.write_str("&apos;"),
+                b => self.write(&[b]),
+            }
+        }
+    }
+
+    /// Return the captured window of bytes, along with a flag indicating if
+    /// the object extends past the end of the window.
+    pub fn finish(self) -> (&'a [u8], bool) {
+        let written = self.total.saturating_sub(self.offset).min(self.buf.len());
+        let more = self.total > self.offset + written;
+        (&self.buf[..written], more)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xfer_writer_window() {
+        let mut buf = [0; 8];
+        let mut w = XferWriter::new(&mut buf, 3, 4);
+        w.write_str("<a n=\"");
+        w.write_xml_escaped(b"&");
+        w.write_str("\"/>");
+
+        let (data, more) = w.finish();
+        assert_eq!(data, b"n=\"&");
+        assert!(more);
+    }
+
+    #[test]
+    fn xfer_writer_last_chunk() {
+        let mut buf = [0; 16];
+        let mut w = XferWriter::new(&mut buf, 2, 16);
+        w.write_hex(0x1234u32);
+        w.write_str(",");
+        w.write_hex(0u8);
+        w.write_str(",");
+        w.write_dec(1024);
+
+        let (data, more) = w.finish();
+        assert_eq!(data, b"1234,0x0,1024");
+        assert!(!more);
+    }
+}
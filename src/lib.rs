@@ -20,6 +20,8 @@
 //!           `ConsoleOutput`.
 //!         - (Extended Mode) Automatically track Attached/Spawned PIDs without
 //!           implementing `ExtendedMode::query_if_attached`.
+//!         - (Breakpoints) Evaluate GDB breakpoint conditions on the target,
+//!           instead of round-tripping to GDB on every breakpoint hit.
 //! - `std` (implies `alloc`)
 //!     - Implement `Connection` for [`TcpStream`](std::net::TcpStream) and
 //!       [`UnixStream`](std::os::unix::net::UnixStream).
@@ -140,6 +142,7 @@ mod util;
 #[doc(hidden)]
 pub mod internal;
 
+pub mod agent;
 pub mod arch;
 pub mod common;
 pub mod target;
@@ -31,7 +31,7 @@ macro_rules! commands {
 
         pub mod ext {
             $(
-                #[allow(non_camel_case_types)]
+                #[allow(non_camel_case_types, clippy::upper_case_acronyms, clippy::enum_variant_names)]
                 pub enum [<$ext:camel>] $(<$lt>)? {
                     $($command(super::$mod::$command<$($lifetime)?>),)*
                 }
@@ -86,7 +86,7 @@ macro_rules! commands {
                 }
                 )*
 
-                Ok(Command::Unknown(buf.into_body_str()))
+                Ok(Command::Unknown(buf.into_body_str().unwrap_or("<binary data>")))
             }
         }
     }};
@@ -103,7 +103,10 @@ pub enum CommandParseError<'a> {
 commands! {
     base use 'a {
         "?" => question_mark::QuestionMark,
+        "bc" => _bc::bc,
+        "bs" => _bs::bs,
         "c" => _c::c<'a>,
+        "C" => _c_upcase::C<'a>,
         "D" => _d_upcase::D,
         "g" => _g::g,
         "G" => _g_upcase::G<'a>,
@@ -113,16 +116,26 @@ commands! {
         "M" => _m_upcase::M<'a>,
         "p" => _p::p,
         "P" => _p_upcase::P<'a>,
+        "QNonStop" => _QNonStop::QNonStop,
         "qAttached" => _qAttached::qAttached,
+        "qCRC" => _qCRC::qCRC<'a>,
         "qfThreadInfo" => _qfThreadInfo::qfThreadInfo,
         "QStartNoAckMode" => _QStartNoAckMode::QStartNoAckMode,
+        "qSearch:memory" => _qSearch_memory::qSearchMemory<'a>,
         "qsThreadInfo" => _qsThreadInfo::qsThreadInfo,
         "qSupported" => _qSupported::qSupported<'a>,
+        "qThreadExtraInfo" => _qThreadExtraInfo::qThreadExtraInfo<'a>,
         "qXfer:features:read" => _qXfer_features_read::qXferFeaturesRead,
+        "qXfer:threads:read" => _qXfer_threads_read::qXferThreadsRead<'a>,
         "s" => _s::s<'a>,
+        "S" => _s_upcase::S<'a>,
         "T" => _t_upcase::T,
         "vCont" => _vCont::vCont<'a>,
+        "vCtrlC" => _vCtrlC::vCtrlC,
         "vKill" => _vKill::vKill,
+        "vStopped" => _vStopped::vStopped,
+        "x" => _x::x<'a>,
+        "X" => _x_upcase::X<'a>,
         "z" => _z::z<'a>,
         "Z" => _z_upcase::Z<'a>,
     }
@@ -147,4 +160,69 @@ commands! {
     section_offsets {
         "qOffsets" => _qOffsets::qOffsets,
     }
+
+    memory_map {
+        "qXfer:memory-map:read" => _qXfer_memory_map::qXferMemoryMapRead,
+    }
+
+    flash use 'a {
+        "vFlashDone" => _vFlashDone::vFlashDone,
+        "vFlashErase" => _vFlashErase::vFlashErase<'a>,
+        "vFlashWrite" => _vFlashWrite::vFlashWrite<'a>,
+    }
+
+    libraries use 'a {
+        "qXfer:libraries:read" => _qXfer_libraries_read::qXferLibrariesRead<'a>,
+    }
+
+    libraries_svr4 use 'a {
+        "qXfer:libraries-svr4:read" => _qXfer_libraries_svr4_read::qXferLibrariesSvr4Read<'a>,
+    }
+
+    auxv {
+        "qXfer:auxv:read" => _qXfer_auxv_read::qXferAuxvRead,
+    }
+
+    exec_file {
+        "qXfer:exec-file:read" => _qXfer_exec_file::qXferExecFileRead,
+    }
+
+    sig_info use 'a {
+        "qXfer:siginfo:read" => _qXfer_siginfo_read::qXferSigInfoRead,
+        "qXfer:siginfo:write" => _qXfer_siginfo_write::qXferSigInfoWrite<'a>,
+    }
+
+    os_data use 'a {
+        "qXfer:osdata:read" => _qXfer_osdata_read::qXferOsDataRead<'a>,
+    }
+
+    branch_trace use 'a {
+        "Qbtrace:" => _Qbtrace::Qbtrace,
+        "qXfer:btrace-conf:read" => _qXfer_btrace_conf_read::qXferBtraceConfRead<'a>,
+        "qXfer:btrace:read" => _qXfer_btrace_read::qXferBtraceRead<'a>,
+    }
+
+    file_io {
+        "F" => _f_upcase::F,
+    }
+
+    host_io use 'a {
+        "vFile:close" => _vFile_close::vFileClose,
+        "vFile:fstat" => _vFile_fstat::vFileFstat,
+        "vFile:open" => _vFile_open::vFileOpen<'a>,
+        "vFile:pread" => _vFile_pread::vFilePread<'a>,
+        "vFile:pwrite" => _vFile_pwrite::vFilePwrite<'a>,
+        "vFile:readlink" => _vFile_readlink::vFileReadlink<'a>,
+        "vFile:setfs" => _vFile_setfs::vFileSetfs,
+        "vFile:unlink" => _vFile_unlink::vFileUnlink<'a>,
+    }
+
+    tracepoints use 'a {
+        "QTDP" => _QTDP::QTDP<'a>,
+        "QTFrame" => _QTFrame::QTFrame<'a>,
+        "QTinit" => _QTinit::QTinit,
+        "QTStart" => _QTStart::QTStart,
+        "QTStop" => _QTStop::QTStop,
+        "qTStatus" => _qTStatus::qTStatus,
+    }
 }
@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QNonStop {
+    pub enable: bool,
+}
+
+impl<'a> ParseCommand<'a> for QNonStop {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        let enable = match body as &[u8] {
+            b":0" => false,
+            b":1" => true,
+            _ => return None,
+        };
+        Some(QNonStop { enable })
+    }
+}
@@ -0,0 +1,251 @@
+use super::prelude::*;
+
+use super::_z_upcase::split_bytecode;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
+pub enum QTDP<'a> {
+    /// `QTDP:n:addr:ena:step:pass[:Fflen][:Xlen,bytes][-]`
+    Create(CreateTDP<'a>),
+    /// `QTDP:-n:addr:[S]action...[-]`
+    Extend(ExtendTDP<'a>),
+}
+
+#[derive(Debug)]
+pub struct CreateTDP<'a> {
+    pub number: usize,
+    pub addr: &'a [u8],
+    pub enable: bool,
+    pub step: u64,
+    pub pass: u64,
+    pub condition: Option<&'a [u8]>,
+}
+
+#[derive(Debug)]
+pub struct ExtendTDP<'a> {
+    pub number: usize,
+    pub addr: &'a [u8],
+    pub while_stepping: bool,
+    pub actions: TdpActions<'a>,
+}
+
+impl<'a> ParseCommand<'a> for QTDP<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        // a trailing '-' indicates that more `QTDP` packets will follow
+        let body = match body {
+            [body @ .., b'-'] => body,
+            body => body,
+        };
+
+        match body {
+            [b'-', body @ ..] => {
+                let mut s = body.splitn_mut(3, |b| *b == b':');
+                let number = decode_hex(s.next()?).ok()?;
+                let addr = decode_hex_buf(s.next()?).ok()?;
+                let (while_stepping, actions) = match s.next()? {
+                    [b'S', actions @ ..] => (true, actions),
+                    actions => (false, actions),
+                };
+
+                Some(QTDP::Extend(ExtendTDP {
+                    number,
+                    addr,
+                    while_stepping,
+                    actions: TdpActions(actions),
+                }))
+            }
+            body => {
+                let mut s = body.split_mut(|b| *b == b':');
+                let number = decode_hex(s.next()?).ok()?;
+                let addr = decode_hex_buf(s.next()?).ok()?;
+                let enable = match s.next()? {
+                    [b'E'] => true,
+                    [b'D'] => false,
+                    _ => return None,
+                };
+                let step = decode_hex(s.next()?).ok()?;
+                let pass = decode_hex(s.next()?).ok()?;
+
+                // fast tracepoints (`F`) and static tracepoints (`S`) aren't advertised as
+                // supported, so GDB shouldn't be sending them.
+                let mut condition = None;
+                for field in s.filter(|f| f.first() == Some(&b'X')) {
+                    let (expr, rest) = split_bytecode(field)?;
+                    if !rest.is_empty() {
+                        return None;
+                    }
+                    condition = Some(decode_hex_buf(expr).ok()? as &[u8]);
+                }
+
+                Some(QTDP::Create(CreateTDP {
+                    number,
+                    addr,
+                    enable,
+                    step,
+                    pass,
+                    condition,
+                }))
+            }
+        }
+    }
+}
+
+/// A lazily evaluated iterator over the actions specified in a `QTDP` packet.
+#[derive(Debug)]
+pub struct TdpActions<'a>(&'a mut [u8]);
+
+impl<'a> TdpActions<'a> {
+    pub fn into_iter(self) -> impl Iterator<Item = Option<TdpAction<'a>>> + 'a {
+        let mut rest = self.0;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+
+            // on error, `rest` is left empty, which ends the iteration
+            let (kind, body, next) = match split_action(core::mem::take(&mut rest)) {
+                Some(action) => action,
+                None => return Some(None),
+            };
+            rest = next;
+
+            Some(TdpAction::from_bytes(kind, body))
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum TdpAction<'a> {
+    /// `R<mask>`
+    Registers(&'a [u8]),
+    /// `M<basereg>,<offset>,<len>`
+    Memory {
+        basereg: u32,
+        offset: u64,
+        len: u64,
+    },
+    /// `X<len>,<bytecode>`
+    Expression(&'a [u8]),
+}
+
+impl<'a> TdpAction<'a> {
+    fn from_bytes(kind: u8, body: &'a mut [u8]) -> Option<TdpAction<'a>> {
+        let action = match kind {
+            b'R' if !body.is_empty() => TdpAction::Registers(decode_hex_buf(body).ok()?),
+            b'M' => {
+                let mut s = body.split(|b| *b == b',');
+                TdpAction::Memory {
+                    basereg: decode_hex(s.next()?).ok()?,
+                    offset: decode_hex(s.next()?).ok()?,
+                    len: decode_hex(s.next()?).ok()?,
+                }
+            }
+            b'X' => TdpAction::Expression(decode_hex_buf(body).ok()?),
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// Split the first action off of `buf`, returning the action's kind and (still
+/// hex-encoded) body, along with the remainder of the buffer.
+fn split_action(buf: &mut [u8]) -> Option<(u8, &mut [u8], &mut [u8])> {
+    let kind = *buf.first()?;
+    match kind {
+        b'X' => {
+            let (expr, rest) = split_bytecode(buf)?;
+            Some((kind, expr, rest))
+        }
+        b'R' | b'M' => {
+            let buf = &mut buf[1..];
+            let end = buf
+                .iter()
+                .position(|b| !(b.is_ascii_hexdigit() || *b == b','))
+                .unwrap_or(buf.len());
+            let (body, rest) = buf.split_at_mut(end);
+            Some((kind, body, rest))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"QTDP".len());
+        };
+    }
+
+    #[test]
+    fn valid_QTDP_create() {
+        test_buf!(buf, b"QTDP:1:55550060:E:0:0:X3,220127-");
+
+        let tdp = match QTDP::from_packet(buf).unwrap() {
+            QTDP::Create(tdp) => tdp,
+            _ => panic!(),
+        };
+
+        assert_eq!(tdp.number, 1);
+        assert_eq!(tdp.addr, &[0x55, 0x55, 0x00, 0x60]);
+        assert!(tdp.enable);
+        assert_eq!(tdp.step, 0);
+        assert_eq!(tdp.pass, 0);
+        assert_eq!(tdp.condition, Some(&[0x22, 0x01, 0x27][..]));
+    }
+
+    #[test]
+    fn valid_QTDP_extend() {
+        test_buf!(buf, b"QTDP:-1:55550060:SR8003MFFFFFFFF,1000,4X1,27-");
+
+        let tdp = match QTDP::from_packet(buf).unwrap() {
+            QTDP::Extend(tdp) => tdp,
+            _ => panic!(),
+        };
+
+        assert_eq!(tdp.number, 1);
+        assert!(tdp.while_stepping);
+
+        let mut actions = tdp.actions.into_iter();
+        match actions.next() {
+            Some(Some(TdpAction::Registers(mask))) => assert_eq!(mask, &[0x80, 0x03]),
+            _ => panic!(),
+        }
+        match actions.next() {
+            Some(Some(TdpAction::Memory {
+                basereg,
+                offset,
+                len,
+            })) => assert_eq!((basereg, offset, len), (0xffff_ffff, 0x1000, 4)),
+            _ => panic!(),
+        }
+        match actions.next() {
+            Some(Some(TdpAction::Expression(expr))) => assert_eq!(expr, &[0x27]),
+            _ => panic!(),
+        }
+        assert!(actions.next().is_none());
+    }
+
+    #[test]
+    fn invalid_QTDP_extend_action() {
+        test_buf!(buf, b"QTDP:-1:55550060:Q1234");
+
+        let tdp = match QTDP::from_packet(buf).unwrap() {
+            QTDP::Extend(tdp) => tdp,
+            _ => panic!(),
+        };
+
+        let mut actions = tdp.actions.into_iter();
+        assert!(matches!(actions.next(), Some(None)));
+        assert!(actions.next().is_none());
+    }
+}
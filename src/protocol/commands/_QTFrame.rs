@@ -0,0 +1,41 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub enum QTFrame<'a> {
+    Number(u32),
+    Pc(&'a [u8]),
+    Tdp(usize),
+    Range(&'a [u8], &'a [u8]),
+    Outside(&'a [u8], &'a [u8]),
+    /// Sent as frame number `-1`
+    Exit,
+}
+
+impl<'a> ParseCommand<'a> for QTFrame<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut s = body.split_mut(|b| *b == b':');
+        let frame = match s.next()? as &[u8] {
+            b"pc" => QTFrame::Pc(decode_hex_buf(s.next()?).ok()?),
+            b"tdp" => QTFrame::Tdp(decode_hex(s.next()?).ok()?),
+            b"range" => QTFrame::Range(
+                decode_hex_buf(s.next()?).ok()?,
+                decode_hex_buf(s.next()?).ok()?,
+            ),
+            b"outside" => QTFrame::Outside(
+                decode_hex_buf(s.next()?).ok()?,
+                decode_hex_buf(s.next()?).ok()?,
+            ),
+            n => match decode_hex(n).ok()? {
+                0xffff_ffff => QTFrame::Exit,
+                n => QTFrame::Number(n),
+            },
+        };
+
+        Some(frame)
+    }
+}
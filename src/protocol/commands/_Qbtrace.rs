@@ -0,0 +1,24 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub enum Qbtrace {
+    /// `Qbtrace:bts`
+    Bts,
+    /// `Qbtrace:pt`
+    Pt,
+    /// `Qbtrace:off`
+    Off,
+}
+
+impl<'a> ParseCommand<'a> for Qbtrace {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        let cmd = match body as &[u8] {
+            b"bts" => Qbtrace::Bts,
+            b"pt" => Qbtrace::Pt,
+            b"off" => Qbtrace::Off,
+            _ => return None,
+        };
+        Some(cmd)
+    }
+}
@@ -0,0 +1,72 @@
+use super::prelude::*;
+
+/// `Fretcode[,errno[,C]][;attachment]`
+#[derive(Debug)]
+pub struct F {
+    pub retcode: i64,
+    pub errno: Option<u32>,
+    pub ctrl_c: bool,
+}
+
+impl<'a> ParseCommand<'a> for F {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        // none of the supported syscalls include a call-specific attachment
+        let body = body.split(|b| *b == b';').next()?;
+
+        let mut s = body.split(|b| *b == b',');
+        let retcode = match s.next()? {
+            [b'-', retcode @ ..] => -decode_hex::<i64>(retcode).ok()?,
+            retcode => decode_hex(retcode).ok()?,
+        };
+        let errno = match s.next() {
+            Some(errno) => Some(decode_hex(errno).ok()?),
+            None => None,
+        };
+        let ctrl_c = match s.next() {
+            Some(b"C") => true,
+            Some(_) => return None,
+            None => false,
+        };
+
+        Some(F {
+            retcode,
+            errno,
+            ctrl_c,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"F".len());
+        };
+    }
+
+    #[test]
+    fn valid_F_success() {
+        test_buf!(buf, b"F1a");
+
+        let f = F::from_packet(buf).unwrap();
+        assert_eq!(f.retcode, 0x1a);
+        assert_eq!(f.errno, None);
+        assert!(!f.ctrl_c);
+    }
+
+    #[test]
+    fn valid_F_interrupted() {
+        test_buf!(buf, b"F-1,4,C");
+
+        let f = F::from_packet(buf).unwrap();
+        assert_eq!(f.retcode, -1);
+        assert_eq!(f.errno, Some(4));
+        assert!(f.ctrl_c);
+    }
+}
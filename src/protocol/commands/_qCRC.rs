@@ -0,0 +1,49 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qCRC<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qCRC<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let (body, buf) = buf.split_at_mut(body_range.end);
+        let body = match &mut body[body_range.start..] {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.split_mut(|b| *b == b',');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        // the remainder of the packet buffer is used to read memory in chunks
+        Some(qCRC { addr, len, buf })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qCRC".len());
+        };
+    }
+
+    #[test]
+    fn valid_qCRC() {
+        test_buf!(buf, b"qCRC:8000,1f4");
+
+        let cmd = qCRC::from_packet(buf).unwrap();
+        assert_eq!(cmd.addr, &[0x80, 0x00]);
+        assert_eq!(cmd.len, 0x1f4);
+    }
+}
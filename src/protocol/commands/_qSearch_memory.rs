@@ -0,0 +1,62 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qSearchMemory<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+    pub pattern: &'a [u8],
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qSearchMemory<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let (body, buf) = buf.split_at_mut(body_range.end);
+        let body = match &mut body[body_range.start..] {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        // the pattern is binary, and may contain ';' characters
+        let mut body = body.splitn_mut(3, |b| *b == b';');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+        let pattern = decode_bin_buf(body.next()?).ok()?;
+
+        if pattern.is_empty() {
+            return None;
+        }
+
+        // the remainder of the packet buffer is used to read memory in chunks
+        Some(qSearchMemory {
+            addr,
+            len,
+            pattern,
+            buf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qSearch:memory".len());
+        };
+    }
+
+    #[test]
+    fn valid_qSearchMemory() {
+        test_buf!(buf, b"qSearch:memory:8000;10000;ab;}\x03");
+
+        let cmd = qSearchMemory::from_packet(buf).unwrap();
+        assert_eq!(cmd.addr, &[0x80, 0x00]);
+        assert_eq!(cmd.len, 0x10000);
+        assert_eq!(cmd.pattern, b"ab;#");
+    }
+}
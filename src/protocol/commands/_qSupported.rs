@@ -7,10 +7,10 @@ pub struct qSupported<'a> {
 
 impl<'a> ParseCommand<'a> for qSupported<'a> {
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
-        let body = buf.into_body();
-        if body.is_empty() {
-            return None;
-        }
+        let body = match buf.into_body() {
+            [b':', body @ ..] if !body.is_empty() => body,
+            _ => return None,
+        };
 
         Some(qSupported {
             features: Features(body),
@@ -56,7 +56,7 @@ pub enum FeatureSupported {
 
 #[derive(Debug)]
 pub struct Feature<'a> {
-    name: Bstr<'a>,
-    val: Option<Bstr<'a>>,
-    status: FeatureSupported,
+    pub name: Bstr<'a>,
+    pub val: Option<Bstr<'a>>,
+    pub status: FeatureSupported,
 }
@@ -0,0 +1,21 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qThreadExtraInfo<'a> {
+    pub thread: ThreadId,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qThreadExtraInfo<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let thread = match &buf[body_range] {
+            [b',', thread @ ..] => thread.try_into().ok()?,
+            _ => return None,
+        };
+
+        // the entire packet buffer is re-used to hold the thread's description
+        Some(qThreadExtraInfo { thread, buf })
+    }
+}
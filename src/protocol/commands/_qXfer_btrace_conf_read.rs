@@ -0,0 +1,33 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferBtraceConfRead<'a> {
+    pub offset: usize,
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qXferBtraceConfRead<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let body = &buf[body_range];
+
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut body = body.split(|b| *b == b':').skip(1);
+        let annex = body.next()?;
+        if !annex.is_empty() {
+            return None;
+        }
+
+        let mut body = body.next()?.split(|b| *b == b',');
+        let offset = decode_hex(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        // the entire packet buffer is re-used to serialize the branch trace configuration
+        Some(qXferBtraceConfRead { offset, len, buf })
+    }
+}
@@ -0,0 +1,39 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferBtraceRead<'a> {
+    pub annex: &'a [u8],
+    pub offset: usize,
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qXferBtraceRead<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let (body, buf) = buf.split_at_mut(body_range.end);
+        let body: &[u8] = body;
+        let body = &body[body_range.start..];
+
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut body = body.split(|b| *b == b':').skip(1);
+        let annex = body.next()?;
+
+        let mut body = body.next()?.split(|b| *b == b',');
+        let offset = decode_hex(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        // the remainder of the packet buffer is used to serialize the branch trace
+        Some(qXferBtraceRead {
+            annex,
+            offset,
+            len,
+            buf,
+        })
+    }
+}
+
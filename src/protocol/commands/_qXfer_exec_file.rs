@@ -0,0 +1,62 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferExecFileRead {
+    pub pid: Option<Pid>,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl<'a> ParseCommand<'a> for qXferExecFileRead {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut body = body.split(|b| *b == b':').skip(1);
+        // GDB omits the pid when the multiprocess extensions aren't in use
+        let pid = match body.next()? {
+            [] => None,
+            pid => Some(Pid::new(decode_hex(pid).ok()?)?),
+        };
+
+        let mut body = body.next()?.split(|b| *b == b',');
+        let offset = decode_hex(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        Some(qXferExecFileRead { pid, offset, len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qXfer:exec-file:read".len());
+        };
+    }
+
+    #[test]
+    fn valid_qXferExecFileRead() {
+        test_buf!(buf, b"qXfer:exec-file:read:1a2b:0,fff");
+
+        let cmd = qXferExecFileRead::from_packet(buf).unwrap();
+        assert_eq!(cmd.pid, Pid::new(0x1a2b));
+        assert_eq!((cmd.offset, cmd.len), (0, 0xfff));
+    }
+
+    #[test]
+    fn valid_qXferExecFileRead_no_pid() {
+        test_buf!(buf, b"qXfer:exec-file:read::10,20");
+
+        let cmd = qXferExecFileRead::from_packet(buf).unwrap();
+        assert_eq!(cmd.pid, None);
+        assert_eq!((cmd.offset, cmd.len), (0x10, 0x20));
+    }
+}
@@ -0,0 +1,82 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferLibrariesSvr4Read<'a> {
+    pub start: Option<u64>,
+    pub prev: Option<u64>,
+    pub offset: usize,
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qXferLibrariesSvr4Read<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let body = &buf[body_range];
+
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut body = body.split(|b| *b == b':').skip(1);
+
+        // the annex is a list of `name=value;` pairs (or empty)
+        let mut start = None;
+        let mut prev = None;
+        for arg in body.next()?.split(|b| *b == b';') {
+            let mut arg = arg.splitn(2, |b| *b == b'=');
+            match (arg.next()?, arg.next()) {
+                (b"start", Some(val)) => start = Some(decode_hex(val).ok()?),
+                (b"prev", Some(val)) => prev = Some(decode_hex(val).ok()?),
+                // ignore any unknown (or empty) arguments
+                _ => {}
+            }
+        }
+
+        let mut body = body.next()?.split(|b| *b == b',');
+        let offset = decode_hex(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        // the entire packet buffer is re-used to serialize the library list
+        Some(qXferLibrariesSvr4Read {
+            start,
+            prev,
+            offset,
+            len,
+            buf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qXfer:libraries-svr4:read".len());
+        };
+    }
+
+    #[test]
+    fn valid_qXferLibrariesSvr4Read() {
+        test_buf!(buf, b"qXfer:libraries-svr4:read::0,fff");
+
+        let cmd = qXferLibrariesSvr4Read::from_packet(buf).unwrap();
+        assert_eq!((cmd.start, cmd.prev), (None, None));
+        assert_eq!((cmd.offset, cmd.len), (0, 0xfff));
+    }
+
+    #[test]
+    fn valid_qXferLibrariesSvr4Read_incremental() {
+        test_buf!(buf, b"qXfer:libraries-svr4:read:start=7ffff7ffe190;prev=7ffff7fc3000:0,fff");
+
+        let cmd = qXferLibrariesSvr4Read::from_packet(buf).unwrap();
+        assert_eq!(cmd.start, Some(0x7fff_f7ff_e190));
+        assert_eq!(cmd.prev, Some(0x7fff_f7fc_3000));
+        assert_eq!((cmd.offset, cmd.len), (0, 0xfff));
+    }
+}
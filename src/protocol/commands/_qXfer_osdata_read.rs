@@ -0,0 +1,60 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferOsDataRead<'a> {
+    pub annex: &'a [u8],
+    pub offset: usize,
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qXferOsDataRead<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let (buf, body_range) = buf.into_raw_buf();
+        let (body, buf) = buf.split_at_mut(body_range.end);
+        let body: &[u8] = body;
+        let body = &body[body_range.start..];
+
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut body = body.split(|b| *b == b':').skip(1);
+        let annex = body.next()?;
+
+        let mut body = body.next()?.split(|b| *b == b',');
+        let offset = decode_hex(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+
+        // the remainder of the packet buffer is used to serialize the table
+        Some(qXferOsDataRead {
+            annex,
+            offset,
+            len,
+            buf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qXfer:osdata:read".len());
+        };
+    }
+
+    #[test]
+    fn valid_qXferOsDataRead() {
+        test_buf!(buf, b"qXfer:osdata:read:processes:40,fff");
+
+        let cmd = qXferOsDataRead::from_packet(buf).unwrap();
+        assert_eq!(cmd.annex, b"processes");
+        assert_eq!((cmd.offset, cmd.len), (0x40, 0xfff));
+    }
+}
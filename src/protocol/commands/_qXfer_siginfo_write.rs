@@ -0,0 +1,49 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qXferSigInfoWrite<'a> {
+    pub offset: usize,
+    pub data: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for qXferSigInfoWrite<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        // the data is binary, and may contain ':' characters
+        let mut body = body.splitn_mut(3, |b| *b == b':');
+        let annex = body.next()?;
+        if !annex.is_empty() {
+            return None;
+        }
+        let offset = decode_hex(body.next()?).ok()?;
+        let data = decode_bin_buf(body.next()?).ok()?;
+
+        Some(qXferSigInfoWrite { offset, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"qXfer:siginfo:write".len());
+        };
+    }
+
+    #[test]
+    fn valid_qXferSigInfoWrite() {
+        test_buf!(buf, b"qXfer:siginfo:write::10:\x0b:}\x03\x00");
+
+        let cmd = qXferSigInfoWrite::from_packet(buf).unwrap();
+        assert_eq!(cmd.offset, 0x10);
+        assert_eq!(cmd.data, b"\x0b:#\x00");
+    }
+}
@@ -0,0 +1,20 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct S<'a> {
+    pub sig: u8,
+    pub addr: Option<&'a [u8]>,
+}
+
+impl<'a> ParseCommand<'a> for S<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        let mut body = body.splitn_mut(2, |b| *b == b';');
+        let sig = decode_hex(body.next()?).ok()?;
+        let addr = match body.next() {
+            Some(addr) => Some(decode_hex_buf(addr).ok()? as &[u8]),
+            None => None,
+        };
+        Some(S { sig, addr })
+    }
+}
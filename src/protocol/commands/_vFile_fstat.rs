@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFileFstat {
+    pub fd: u32,
+}
+
+impl<'a> ParseCommand<'a> for vFileFstat {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        match body {
+            [b':', fd @ ..] => Some(vFileFstat {
+                fd: decode_hex(fd).ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFileOpen<'a> {
+    pub filename: &'a [u8],
+    pub flags: u32,
+    pub mode: u32,
+}
+
+impl<'a> ParseCommand<'a> for vFileOpen<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        match body {
+            [b':', body @ ..] => {
+                let mut body = body.splitn_mut(3, |b| *b == b',');
+                let filename = decode_hex_buf(body.next()?).ok()?;
+                let flags = decode_hex(body.next()?).ok()?;
+                let mode = decode_hex(body.next()?).ok()?;
+                Some(vFileOpen {
+                    filename,
+                    flags,
+                    mode,
+                })
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFilePread<'a> {
+    pub fd: u32,
+    pub count: usize,
+    pub offset: u64,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFilePread<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        // all the packet's arguments are decoded into integers, so the _entire_
+        // packet buffer can be reused to hold the data read from the file.
+        let (buf, body_range) = buf.into_raw_buf();
+        let body = match &buf[body_range] {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.split(|b| *b == b',');
+        let fd = decode_hex(body.next()?).ok()?;
+        let count = decode_hex(body.next()?).ok()?;
+        let offset = decode_hex(body.next()?).ok()?;
+
+        Some(vFilePread {
+            fd,
+            count,
+            offset,
+            buf,
+        })
+    }
+}
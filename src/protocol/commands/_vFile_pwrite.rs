@@ -0,0 +1,25 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFilePwrite<'a> {
+    pub fd: u32,
+    pub offset: u64,
+    pub data: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFilePwrite<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        match body {
+            [b':', body @ ..] => {
+                // the data is binary, and may contain ',' characters
+                let mut body = body.splitn_mut(3, |b| *b == b',');
+                let fd = decode_hex(body.next()?).ok()?;
+                let offset = decode_hex(body.next()?).ok()?;
+                let data = decode_bin_buf(body.next()?).ok()?;
+                Some(vFilePwrite { fd, offset, data })
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFileReadlink<'a> {
+    pub filename: &'a [u8],
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFileReadlink<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        // the filename is decoded in-place, and the remainder of the packet
+        // buffer is used to hold the link's value.
+        let (buf, body_range) = buf.into_raw_buf();
+        let filename_len = match &mut buf[body_range.clone()] {
+            [b':', filename @ ..] => decode_hex_buf(filename).ok()?.len(),
+            _ => return None,
+        };
+
+        let filename_start = body_range.start + b":".len();
+        let (filename, buf) = buf.split_at_mut(filename_start + filename_len);
+        let filename = &filename[filename_start..];
+
+        Some(vFileReadlink { filename, buf })
+    }
+}
@@ -0,0 +1,19 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFileSetfs {
+    /// `None` corresponds to the remote stub's filesystem (i.e: pid 0)
+    pub pid: Option<Pid>,
+}
+
+impl<'a> ParseCommand<'a> for vFileSetfs {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        match body {
+            [b':', pid @ ..] => Some(vFileSetfs {
+                pid: Pid::new(decode_hex(pid).ok()?),
+            }),
+            _ => None,
+        }
+    }
+}
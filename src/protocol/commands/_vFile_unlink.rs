@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFileUnlink<'a> {
+    pub filename: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFileUnlink<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+        match body {
+            [b':', filename @ ..] => Some(vFileUnlink {
+                filename: decode_hex_buf(filename).ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
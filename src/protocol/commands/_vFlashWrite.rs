@@ -0,0 +1,45 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFlashWrite<'a> {
+    pub addr: &'a [u8],
+    pub val: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFlashWrite<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        // the data is binary, and may contain ':' characters
+        let mut body = body.splitn_mut(2, |b| *b == b':');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let val = decode_bin_buf(body.next()?).ok()?;
+
+        Some(vFlashWrite { addr, val })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"vFlashWrite".len());
+        };
+    }
+
+    #[test]
+    fn valid_vFlashWrite() {
+        test_buf!(buf, b"vFlashWrite:08000000:\x01:}\x03\x04");
+
+        let cmd = vFlashWrite::from_packet(buf).unwrap();
+        assert_eq!(cmd.addr, &[0x08, 0x00, 0x00, 0x00]);
+        assert_eq!(cmd.val, b"\x01:#\x04");
+    }
+}
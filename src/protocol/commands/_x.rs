@@ -0,0 +1,20 @@
+use super::prelude::*;
+
+use super::_m::m;
+
+/// `x addr,length` shares its syntax with `m`, and only differs in how the
+/// response is encoded (binary instead of hex).
+#[derive(Debug)]
+pub struct x<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for x<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let m { addr, len, buf } = m::from_packet(buf)?;
+        Some(x { addr, len, buf })
+    }
+}
@@ -0,0 +1,55 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct X<'a> {
+    pub addr: &'a [u8],
+    pub val: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for X<'a> {
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        // the data is binary, and may contain ',' and ':' characters
+        let mut body = body.splitn_mut(2, |b| *b == b':');
+        let mut params = body.next()?.split_mut(|b| *b == b',');
+        let addr = decode_hex_buf(params.next()?).ok()?;
+        let len: usize = decode_hex(params.next()?).ok()?;
+        let val = decode_bin_buf(body.next()?).ok()?;
+
+        if val.len() != len {
+            return None;
+        }
+
+        Some(X { addr, val })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"X".len());
+        };
+    }
+
+    #[test]
+    fn valid_X() {
+        test_buf!(buf, b"X1000,4:\x01,:}\x03");
+
+        let cmd = X::from_packet(buf).unwrap();
+        assert_eq!(cmd.addr, &[0x10, 0x00]);
+        assert_eq!(cmd.val, b"\x01,:#");
+    }
+
+    #[test]
+    fn invalid_X_len_mismatch() {
+        test_buf!(buf, b"X1000,4:\x01\x02");
+
+        assert!(X::from_packet(buf).is_none());
+    }
+}
@@ -6,19 +6,124 @@ pub struct Z<'a> {
     pub addr: &'a [u8],
     /// architecture dependent
     pub kind: u8,
-    // TODO: Add support for breakpoint 'conds', 'persist', and 'cmds' feature
+    /// Agent expression bytecode for target-side breakpoint conditions.
+    pub conds: Option<BytecodeList<'a>>,
+    // TODO: Add support for breakpoint 'persist', and 'cmds' feature
+}
+
+/// A lazily decoded list of agent expressions, sent as a series of
+/// `X<len>,<hex bytecode>` entries.
+#[derive(Debug)]
+pub struct BytecodeList<'a>(&'a mut [u8]);
+
+impl<'a> BytecodeList<'a> {
+    pub fn into_iter(self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let mut rest = self.0;
+        core::iter::from_fn(move || {
+            let (expr, next) = split_bytecode(core::mem::take(&mut rest))?;
+            rest = next;
+            // the `from_packet` method guarantees that the bytecode is valid hex ascii, so this
+            // method should never fail.
+            Some(decode_hex_buf(expr).unwrap_or(&mut []) as &[u8])
+        })
+    }
+}
+
+/// Split the first `X<len>,<hex bytecode>` entry off of `buf`, returning the
+/// (still hex-encoded) bytecode along with the remainder of the buffer.
+pub(super) fn split_bytecode(buf: &mut [u8]) -> Option<(&mut [u8], &mut [u8])> {
+    let buf = match buf.split_first_mut()? {
+        (b'X', buf) => buf,
+        _ => return None,
+    };
+
+    let comma = buf.iter().position(|b| *b == b',')?;
+    let (len, buf) = buf.split_at_mut(comma);
+    let len: usize = decode_hex(len).ok()?;
+    let buf = &mut buf[1..];
+
+    let hex_len = len.checked_mul(2)?;
+    if buf.len() < hex_len {
+        return None;
+    }
+    Some(buf.split_at_mut(hex_len))
 }
 
 impl<'a> ParseCommand<'a> for Z<'a> {
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
         let body = buf.into_body();
-        let mut body = body.split_mut(|&b| b == b',');
-        let type_ = decode_hex(body.next()?).ok()?;
-        let addr = decode_hex_buf(body.next()?).ok()?;
-        let kind = decode_hex(body.next()?).ok()?;
+        let mut body = body.splitn_mut(3, |b| *b == b';');
+
+        let mut params = body.next()?.split_mut(|&b| b == b',');
+        let type_ = decode_hex(params.next()?).ok()?;
+        let addr = decode_hex_buf(params.next()?).ok()?;
+        let kind = decode_hex(params.next()?).ok()?;
+
+        // TODO: properly parse 'persist', and 'cmds' fields in 'Z' packets
+        let conds = match body.next() {
+            Some(conds) if conds.first() == Some(&b'X') => {
+                // validate the bytecode list up-front. this removes all the error handling from
+                // the lazy `BytecodeList` iterator.
+                let mut rest = &mut *conds;
+                while !rest.is_empty() {
+                    let (expr, next) = split_bytecode(rest)?;
+                    if !expr.iter().all(|b| is_hex(*b)) {
+                        return None;
+                    }
+                    rest = next;
+                }
+                Some(BytecodeList(conds))
+            }
+            _ => None,
+        };
+
+        Some(Z {
+            type_,
+            addr,
+            kind,
+            conds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_buf {
+        ($bufname:ident, $body:literal) => {
+            let mut test = $body.to_vec();
+            let buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+            let $bufname = buf.trim_start_body_bytes(b"Z".len());
+        };
+    }
+
+    #[test]
+    fn valid_Z_no_conds() {
+        test_buf!(buf, b"Z0,1234,4");
+
+        let pkt = Z::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.type_, 0);
+        assert_eq!(pkt.addr, &[0x12, 0x34]);
+        assert_eq!(pkt.kind, 4);
+        assert!(pkt.conds.is_none());
+    }
+
+    #[test]
+    fn valid_Z_conds() {
+        test_buf!(buf, b"Z0,1234,4;X3,220127X1,27;cmds:0,X1,27");
+
+        let pkt = Z::from_packet(buf).unwrap();
+        let conds = pkt.conds.unwrap().into_iter().collect::<Vec<_>>();
+
+        assert_eq!(conds, &[&[0x22, 0x01, 0x27][..], &[0x27][..]]);
+    }
 
-        // TODO: properly parse 'conds', 'persist', and 'cmds' fields in 'Z' packets
+    #[test]
+    fn invalid_Z_conds_truncated() {
+        test_buf!(buf, b"Z0,1234,4;X3,2201");
 
-        Some(Z { type_, addr, kind })
+        assert!(Z::from_packet(buf).is_none());
     }
 }
@@ -87,6 +87,38 @@ pub fn decode_hex_buf(base_buf: &mut [u8]) -> Result<&mut [u8], DecodeHexBufErro
     Ok(&mut base_buf[..decoded_len + odd_adust])
 }
 
+#[derive(Debug)]
+pub enum DecodeBinBufError {
+    UnexpectedEnd,
+}
+
+/// Decode GDB escaped binary bytes into a byte slice _in place_.
+///
+/// The bytes `#`, `$`, `}` and `*` are escaped as `}` followed by the original
+/// byte XOR `0x20`.
+pub fn decode_bin_buf(buf: &mut [u8]) -> Result<&mut [u8], DecodeBinBufError> {
+    use DecodeBinBufError::*;
+
+    let mut i = 0;
+    let mut j = 0;
+    let len = buf.len();
+    while i < len {
+        if buf[i] == b'}' {
+            if i + 1 >= len {
+                return Err(UnexpectedEnd);
+            }
+            buf[j] = buf[i + 1] ^ 0x20;
+            i += 2;
+        } else {
+            buf[j] = buf[i];
+            i += 1;
+        }
+        j += 1;
+    }
+
+    Ok(&mut buf[..j])
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum EncodeHexBufError {
@@ -187,4 +219,17 @@ mod tests {
         let res = decode_hex_buf(&mut payload).unwrap();
         assert_eq!(res, [0x1]);
     }
+
+    #[test]
+    fn decode_bin_buf_escaped() {
+        let mut payload = [b'a', b'}', b'#' ^ 0x20, b'}', b'}' ^ 0x20, b'b'];
+        let res = decode_bin_buf(&mut payload).unwrap();
+        assert_eq!(res, [b'a', b'#', b'}', b'b']);
+    }
+
+    #[test]
+    fn decode_bin_buf_truncated() {
+        let mut payload = [b'a', b'}'];
+        assert!(decode_bin_buf(&mut payload).is_err());
+    }
 }
@@ -21,6 +21,24 @@ pub enum Packet<'a> {
     Command(Command<'a>),
 }
 
+/// Packets whose bodies may contain arbitrary binary data, and are therefore
+/// exempt from ASCII validation.
+const BINARY_PACKETS: &[&[u8]] = &[
+    b"X",
+    b"qSearch:memory:",
+    b"qXfer:siginfo:write:",
+    b"vFile:pwrite:",
+    b"vFlashWrite:",
+];
+
+/// Validate that the packet body is ASCII (unless it's a binary packet).
+fn validate_ascii(body: &[u8]) -> Result<(), PacketParseError> {
+    if !body.is_ascii() && !BINARY_PACKETS.iter().any(|p| body.starts_with(p)) {
+        return Err(PacketParseError::NotASCII);
+    }
+    Ok(())
+}
+
 pub struct PacketBuf<'a> {
     buf: &'a mut [u8],
     body_range: core::ops::Range<usize>,
@@ -29,12 +47,10 @@ pub struct PacketBuf<'a> {
 impl<'a> PacketBuf<'a> {
     /// Validate the contents of the raw packet buffer, checking for checksum
     /// consistency, structural correctness, and ASCII validation.
+    ///
+    /// _Note:_ packets which carry binary data (e.g: `vFile:pwrite`) are not
+    /// validated as ASCII.
     pub fn new(pkt_buf: &'a mut [u8]) -> Result<PacketBuf<'a>, PacketParseError> {
-        // validate the packet is valid ASCII
-        if !pkt_buf.is_ascii() {
-            return Err(PacketParseError::NotASCII);
-        }
-
         let end_of_body = pkt_buf
             .iter()
             .position(|b| *b == b'#')
@@ -45,6 +61,8 @@ impl<'a> PacketBuf<'a> {
         let body = &mut body[1..]; // skip the '$'
         let checksum = &mut checksum[1..][..2]; // skip the '#'
 
+        validate_ascii(body)?;
+
         // validate the checksum
         let checksum = decode_hex(checksum).map_err(|_| PacketParseError::MalformedChecksum)?;
         let calculated = body.iter().fold(0u8, |a, x| a.wrapping_add(*x));
@@ -56,9 +74,10 @@ impl<'a> PacketBuf<'a> {
         }
 
         if log_enabled!(log::Level::Trace) {
-            // SAFETY: body confirmed to be `is_ascii()`
-            let body = unsafe { core::str::from_utf8_unchecked(body) };
-            trace!("<-- ${}#{:02x?}", body, checksum);
+            match core::str::from_utf8(body) {
+                Ok(body) => trace!("<-- ${}#{:02x?}", body, checksum),
+                Err(_) => trace!("<-- ${:x?}#{:02x?}", body, checksum),
+            }
         }
 
         Ok(PacketBuf {
@@ -71,10 +90,7 @@ impl<'a> PacketBuf<'a> {
     /// the header/checksum trimming stage. ASCII validation is still performed.
     #[cfg(test)]
     pub fn new_with_raw_body(body: &'a mut [u8]) -> Result<PacketBuf<'a>, PacketParseError> {
-        // validate the packet is valid ASCII
-        if !body.is_ascii() {
-            return Err(PacketParseError::NotASCII);
-        }
+        validate_ascii(body)?;
 
         let len = body.len();
         Ok(PacketBuf {
@@ -100,10 +116,10 @@ impl<'a> PacketBuf<'a> {
         &mut self.buf[self.body_range]
     }
 
-    pub fn into_body_str(self) -> &'a str {
-        // SAFETY: buffer confirmed to be `is_ascii()` in `new`, and no other PacketBuf
-        // member allow arbitrary modification of `self.buf`.
-        unsafe { core::str::from_utf8_unchecked(&self.buf[self.body_range]) }
+    /// Return the body as a `str`, or `None` if the body isn't valid UTF-8 (e.g:
+    /// when the packet contains binary data).
+    pub fn into_body_str(self) -> Option<&'a str> {
+        core::str::from_utf8(&self.buf[self.body_range]).ok()
     }
 
     /// Return a mut reference to the _entire_ underlying packet buffer, and the
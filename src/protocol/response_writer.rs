@@ -17,6 +17,8 @@ pub struct Error<C>(C);
 /// incoming / outgoing data.
 pub struct ResponseWriter<'a, C: Connection + 'a> {
     inner: &'a mut C,
+    // '$' for regular packets, '%' for asynchronous notifications
+    start_byte: u8,
     started: bool,
     checksum: u8,
     // buffer outgoing message
@@ -31,6 +33,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
     pub fn new(inner: &'a mut C) -> Self {
         Self {
             inner,
+            start_byte: b'$',
             started: false,
             checksum: 0,
             #[cfg(feature = "alloc")]
@@ -38,13 +41,27 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         }
     }
 
+    /// Creates a new ResponseWriter which sends an asynchronous notification
+    /// (i.e: a packet starting with '%' instead of '$').
+    pub fn new_notification(inner: &'a mut C) -> Self {
+        Self {
+            start_byte: b'%',
+            ..Self::new(inner)
+        }
+    }
+
     /// Consumes self, writing out the final '#' and checksum
     pub fn flush(mut self) -> Result<(), Error<C::Error>> {
         // don't include '#' in checksum calculation
         let checksum = self.checksum;
 
         #[cfg(feature = "alloc")]
-        trace!("--> ${}#{:02x?}", self.msg, checksum);
+        trace!(
+            "--> {}{}#{:02x?}",
+            self.start_byte as char,
+            self.msg,
+            checksum
+        );
 
         self.write(b'#')?;
         self.write_hex(checksum)?;
@@ -64,7 +81,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
 
         if !self.started {
             self.started = true;
-            self.inner.write(b'$').map_err(Error)?;
+            self.inner.write(self.start_byte).map_err(Error)?;
         }
 
         self.checksum = self.checksum.wrapping_add(byte);
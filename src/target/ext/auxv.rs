@@ -0,0 +1,20 @@
+//! Provide the target's auxiliary vector.
+//!
+//! On System-V targets (e.g: Linux), the auxiliary vector is passed to a
+//! process by the kernel when it starts, and describes various properties of
+//! the process's environment (e.g: the base address of the dynamic linker via
+//! the `AT_BASE` entry, or the program's entry point via `AT_ENTRY`). GDB uses
+//! it to locate the dynamic linker when debugging dynamically linked programs.
+
+use crate::target::{Target, TargetResult};
+
+/// Target Extension - Provide the target's auxiliary vector.
+pub trait Auxv: Target {
+    /// Return the target's raw auxiliary vector, exactly as it appears in the
+    /// target's memory (i.e: a sequence of `(type, value)` pairs, encoded
+    /// using the target's word size and endianness, terminated by an
+    /// `AT_NULL` entry).
+    fn get_auxv(&mut self) -> TargetResult<&[u8], Self>;
+}
+
+define_ext!(AuxvOps, Auxv);
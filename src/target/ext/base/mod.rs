@@ -0,0 +1,81 @@
+//! Base operations required to debug any target (read/write memory/registers,
+//! step/resume, etc...)
+//!
+//! While not strictly required, it's recommended that single threaded targets
+//! implement the simplified `singlethread` API. Similarly, targets which only
+//! ever debug a single process should implement the `multithread` API instead
+//! of the more general `multiprocess` API.
+
+pub mod multiprocess;
+pub mod multithread;
+pub mod singlethread;
+
+/// Base operations for single/multi threaded and multi process targets.
+pub enum BaseOps<'a, A, E> {
+    /// Single-threaded target
+    SingleThread(&'a mut dyn singlethread::SingleThreadOps<Arch = A, Error = E>),
+    /// Multi-threaded target
+    MultiThread(&'a mut dyn multithread::MultiThreadOps<Arch = A, Error = E>),
+    /// Multi-process target
+    MultiProcess(&'a mut dyn multiprocess::MultiProcessOps<Arch = A, Error = E>),
+}
+
+/// Describes how the target should be resumed.
+///
+/// New resume actions are only ever sent to targets which explicitly opt-in to
+/// them, but as more actions may be added in the future, this enum is marked
+/// `#[non_exhaustive]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResumeAction<U> {
+    /// Continue execution (until the next event occurs).
+    Continue,
+    /// Step forward a single instruction.
+    Step,
+    /// Continue execution, delivering the specified signal to the target.
+    ///
+    /// Targets without a notion of signals may treat this the same as
+    /// `Continue`.
+    ContinueWithSignal(u8),
+    /// Step forward a single instruction, delivering the specified signal to
+    /// the target.
+    ///
+    /// Targets without a notion of signals may treat this the same as `Step`.
+    StepWithSignal(u8),
+    /// Stop the thread (i.e: report a stop reason for it, even if it was
+    /// already stopped).
+    ///
+    /// Only sent to targets running in non-stop mode (see
+    /// [`MultiThreadNonStop`](multithread::MultiThreadNonStop)).
+    Stop,
+    /// Keep stepping forward until the program counter leaves the address
+    /// range `[start, end)`, and then report `DoneStep`.
+    ///
+    /// If some other event occurs while stepping through the range (e.g: a
+    /// breakpoint is hit), that event should be reported instead.
+    ///
+    /// Only sent to targets which opt-in to range stepping (e.g: via
+    /// [`SingleThreadOps::support_range_stepping`](singlethread::SingleThreadOps::support_range_stepping)).
+    StepInRange(U, U),
+    /// Step backwards a single instruction.
+    ///
+    /// Only sent to targets which opt-in to reverse stepping (e.g: via
+    /// [`SingleThreadOps::support_reverse_step`](singlethread::SingleThreadOps::support_reverse_step)).
+    ReverseStep,
+    /// Continue execution backwards (until the next event occurs, or the
+    /// start of the execution history is reached).
+    ///
+    /// Only sent to targets which opt-in to reverse continue (e.g: via
+    /// [`SingleThreadOps::support_reverse_cont`](singlethread::SingleThreadOps::support_reverse_cont)).
+    ReverseContinue,
+}
+
+/// Describes which end of the execution history was reached while executing
+/// in reverse (or replaying a recording).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayLogPosition {
+    /// Reached the beginning of the execution history.
+    Begin,
+    /// Reached the end of the execution history.
+    End,
+}
@@ -0,0 +1,369 @@
+//! Base debugging operations for multi process targets.
+
+use crate::arch::Arch;
+use crate::common::*;
+use crate::target::ext::breakpoints::WatchKind;
+use crate::target::ext::file_io::Syscall;
+use crate::target::{Target, TargetResult};
+
+// Convenient re-exports
+pub use super::{ReplayLogPosition, ResumeAction};
+
+/// Selects a thread (or group of threads) corresponding to a ResumeAction.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ThreadSelector {
+    /// Thread with a specific ID, in a specific process.
+    WithID {
+        /// Process ID
+        pid: Pid,
+        /// Thread ID
+        tid: Tid,
+    },
+    /// All (other) threads in a specific process.
+    AllInProcess(Pid),
+    /// All (other) threads, across all processes.
+    All,
+}
+
+/// Base debugging operations for multi process targets.
+///
+/// Multi process targets are able to debug several processes (GDB
+/// "inferiors") from a single `gdbstub` session, with each thread being
+/// identified by a `(Pid, Tid)` pair.
+///
+/// With the exception of the extra `Pid` argument, the methods on this trait
+/// behave identically to their counterparts on
+/// [`MultiThreadOps`](super::multithread::MultiThreadOps). Please refer to the
+/// documentation on `MultiThreadOps` for implementation requirements and
+/// additional considerations.
+///
+/// _Note:_ `gdbstub` does not support GDB's "Non-Stop" mode on multi process
+/// targets.
+#[allow(clippy::type_complexity)]
+pub trait MultiProcessOps: Target {
+    /// Resume execution on the target.
+    ///
+    /// `actions` is an iterator over `(ThreadSelector, ResumeAction)` pairs
+    /// which specify how various threads should be resumed (i.e: single-step
+    /// vs. resume). It is _guaranteed_ to contain at least one action. It is
+    /// not guaranteed to be exhaustive over all live threads, and any threads
+    /// without a corresponding `ThreadSelector` should be left in the same
+    /// state (if possible).
+    ///
+    /// The `check_gdb_interrupt` callback can be invoked to check if GDB sent
+    /// an Interrupt packet (i.e: the user pressed Ctrl-C). It's recommended to
+    /// invoke this callback every-so-often while the system is running (e.g:
+    /// every X cycles/milliseconds). Periodically checking for incoming
+    /// interrupt packets is _not_ required, but it is _recommended_.
+    fn resume(
+        &mut self,
+        actions: Actions<'_, <Self::Arch as Arch>::Usize>,
+        check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<ProcessStopReason<<Self::Arch as Arch>::Usize>, Self::Error>;
+
+    /// Read the registers of thread `tid` in process `pid`.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn read_registers(
+        &mut self,
+        regs: &mut <Self::Arch as Arch>::Registers,
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Write the registers of thread `tid` in process `pid`.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn write_registers(
+        &mut self,
+        regs: &<Self::Arch as Arch>::Registers,
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Read to a single register of thread `tid` in process `pid`.
+    ///
+    /// Implementations should write the value of the register using target's
+    /// native byte order in the buffer `dst`.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn read_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        dst: &mut [u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, dst, pid, tid);
+        Ok(())
+    }
+
+    /// Write from a single register of thread `tid` in process `pid`.
+    ///
+    /// The `val` buffer contains the new value of the register in the target's
+    /// native byte order. It is guaranteed to be the exact length as the target
+    /// register.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn write_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        val: &[u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, val, pid, tid);
+        Ok(())
+    }
+
+    /// Read bytes from the specified address range, as seen by thread `tid` in
+    /// process `pid`.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate non-fatal
+    /// error should be returned.
+    fn read_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &mut [u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Write bytes to the specified address range, as seen by thread `tid` in
+    /// process `pid`.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate non-fatal
+    /// error should be returned.
+    fn write_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+        pid: Pid,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// List all currently active threads, across all processes.
+    fn list_active_threads(
+        &mut self,
+        thread_is_active: &mut dyn FnMut(Pid, Tid),
+    ) -> Result<(), Self::Error>;
+
+    /// Check if the specified thread is alive.
+    ///
+    /// As a convenience, this method provides a default implementation which
+    /// uses `list_active_threads` to do a linear-search through all active
+    /// threads. On thread-heavy systems, it may be more efficient
+    /// to override this method with a more direct query.
+    fn is_thread_alive(&mut self, pid: Pid, tid: Tid) -> Result<bool, Self::Error> {
+        let mut found = false;
+        self.list_active_threads(&mut |active_pid, active_tid| {
+            if pid == active_pid && tid == active_tid {
+                found = true;
+            }
+        })?;
+        Ok(found)
+    }
+
+    /// Kill process `pid`.
+    ///
+    /// Once all processes have been killed, `gdbstub` ends the debugging
+    /// session with `DisconnectReason::Kill`.
+    ///
+    /// _Note:_ If the target implements
+    /// [`ExtendedMode`](crate::target::ext::extended_mode::ExtendedMode),
+    /// `ExtendedMode::kill` is called instead.
+    fn kill(&mut self, pid: Pid) -> TargetResult<(), Self>;
+
+    /// Detach from process `pid`, letting it run freely.
+    ///
+    /// Once GDB has detached from all processes, `gdbstub` ends the debugging
+    /// session with `DisconnectReason::Disconnect`.
+    fn detach(&mut self, pid: Pid) -> TargetResult<(), Self>;
+
+    /// Support for range stepping (i.e: [`ResumeAction::StepInRange`]).
+    fn support_range_stepping(&mut self) -> Option<MultiProcessRangeSteppingOps<Self>> {
+        None
+    }
+
+    /// Support for reverse stepping (i.e: [`ResumeAction::ReverseStep`]).
+    fn support_reverse_step(&mut self) -> Option<MultiProcessReverseStepOps<Self>> {
+        None
+    }
+
+    /// Support for reverse continue (i.e: [`ResumeAction::ReverseContinue`]).
+    fn support_reverse_cont(&mut self) -> Option<MultiProcessReverseContOps<Self>> {
+        None
+    }
+}
+
+/// Support for [`ResumeAction::StepInRange`], whereby the target keeps
+/// stepping a thread until its PC leaves a given address range.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiProcessOps::resume` is able to handle `ResumeAction::StepInRange`.
+/// Targets which don't implement this extension will never be sent
+/// `StepInRange` actions.
+pub trait MultiProcessRangeStepping: MultiProcessOps {}
+
+define_ext!(MultiProcessRangeSteppingOps, MultiProcessRangeStepping);
+
+/// Support for [`ResumeAction::ReverseStep`], whereby a thread is stepped
+/// backwards a single instruction.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiProcessOps::resume` is able to handle `ResumeAction::ReverseStep`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseStep` actions.
+///
+/// Once there is no more execution history to step back through, the target
+/// should report a `ReplayLog { pos: ReplayLogPosition::Begin, .. }` stop
+/// reason.
+pub trait MultiProcessReverseStep: MultiProcessOps {}
+
+define_ext!(MultiProcessReverseStepOps, MultiProcessReverseStep);
+
+/// Support for [`ResumeAction::ReverseContinue`], whereby the target is run
+/// backwards until the next event occurs (e.g: a breakpoint is hit).
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiProcessOps::resume` is able to handle `ResumeAction::ReverseContinue`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseContinue` actions.
+///
+/// Once there is no more execution history to run back through, the target
+/// should report a `ReplayLog { pos: ReplayLogPosition::Begin, .. }` stop
+/// reason.
+pub trait MultiProcessReverseCont: MultiProcessOps {}
+
+define_ext!(MultiProcessReverseContOps, MultiProcessReverseCont);
+
+/// Describes why a process (or a thread within a process) stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProcessStopReason<U> {
+    /// Completed the single-step request.
+    DoneStep,
+    /// `check_gdb_interrupt` returned `true`
+    GdbInterrupt,
+    /// The entire target halted (i.e: there are no more processes left to
+    /// debug).
+    Halted,
+    /// A process exited with the given status code. Unlike `Halted`, the
+    /// debugging session carries on, as other processes may still be running.
+    Exited {
+        /// Which process exited
+        pid: Pid,
+        /// Exit status code
+        status: u8,
+    },
+    /// A thread hit a software breakpoint (e.g. due to a trap instruction).
+    ///
+    /// NOTE: This does not necessarily have to be a breakpoint configured by
+    /// the client/user of the current GDB session.
+    SwBreak {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread hit the breakpoint
+        tid: Tid,
+    },
+    /// A thread hit a hardware breakpoint.
+    HwBreak {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread hit the breakpoint
+        tid: Tid,
+    },
+    /// A thread hit a watchpoint.
+    Watch {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread hit the watchpoint
+        tid: Tid,
+        /// Kind of watchpoint that was hit
+        kind: WatchKind,
+        /// Address of watched memory
+        addr: U,
+    },
+    /// The program received a signal
+    Signal(u8),
+    /// A specific thread received a signal.
+    SignalWithThread {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread received the signal
+        tid: Tid,
+        /// Signal number
+        signal: u8,
+    },
+    /// A thread requested that GDB perform a system call on it's behalf.
+    ///
+    /// Requires implementing the
+    /// [`FileIo`](crate::target::ext::file_io::FileIo) extension, which is used
+    /// to deliver the syscall's result back to the target.
+    SyscallRequest {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread made the request
+        tid: Tid,
+        /// The requested syscall
+        syscall: Syscall<U>,
+    },
+    /// Reached the beginning (or end) of the execution history while
+    /// executing in reverse, and there is no more history to replay.
+    ReplayLog {
+        /// Process containing the thread
+        pid: Pid,
+        /// Which thread reached the end of the execution history
+        tid: Tid,
+        /// Which end of the execution history was reached
+        pos: ReplayLogPosition,
+    },
+}
+
+/// An iterator of `(ThreadSelector, ResumeAction)` used to specify how threads
+/// should be resumed when running in multi process mode. It is _guaranteed_ to
+/// contain at least one action.
+///
+/// See the documentation for
+/// [`MultiProcessOps::resume`](trait.MultiProcessOps.html#tymethod.resume) for
+/// more details.
+pub struct Actions<'a, U> {
+    inner: &'a mut dyn Iterator<Item = (ThreadSelector, ResumeAction<U>)>,
+}
+
+impl<U> core::fmt::Debug for Actions<'_, U> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Actions {{ .. }}")
+    }
+}
+
+impl<U> Actions<'_, U> {
+    pub(crate) fn new(
+        iter: &mut dyn Iterator<Item = (ThreadSelector, ResumeAction<U>)>,
+    ) -> Actions<'_, U> {
+        Actions { inner: iter }
+    }
+}
+
+impl<U> Iterator for Actions<'_, U> {
+    type Item = (ThreadSelector, ResumeAction<U>);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
@@ -0,0 +1,487 @@
+//! Base debugging operations for multi threaded targets.
+
+use crate::arch::Arch;
+use crate::common::*;
+use crate::target::ext::breakpoints::WatchKind;
+use crate::target::ext::file_io::Syscall;
+use crate::target::{Target, TargetResult};
+
+// Convenient re-exports
+pub use super::{ReplayLogPosition, ResumeAction};
+
+/// Selects a thread corresponding to a ResumeAction.
+// NOTE: this is a subset of the internal `IdKind` type, albeit without an `Any` variant. Selecting
+// `Any` thread is something that's handled by `gdbstub` internally, and shouldn't be exposed to the
+// end user.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TidSelector {
+    /// Thread with a specific ID.
+    WithID(Tid),
+    /// All (other) threads.
+    All,
+}
+
+/// Base debugging operations for multi threaded targets.
+#[allow(clippy::type_complexity)]
+pub trait MultiThreadOps: Target {
+    /// Resume execution on the target.
+    ///
+    /// `actions` is an iterator over `(TidSelector, ResumeAction)` pairs which
+    /// specify how various threads should be resumed (i.e: single-step vs.
+    /// resume). It is _guaranteed_ to contain at least one action. It is not
+    /// guaranteed to be exhaustive over all live threads, and any threads
+    /// without a corresponding `TidSelector` should be left in the same state
+    /// (if possible).
+    ///
+    /// The `check_gdb_interrupt` callback can be invoked to check if GDB sent
+    /// an Interrupt packet (i.e: the user pressed Ctrl-C). It's recommended to
+    /// invoke this callback every-so-often while the system is running (e.g:
+    /// every X cycles/milliseconds). Periodically checking for incoming
+    /// interrupt packets is _not_ required, but it is _recommended_.
+    ///
+    /// # Implementation requirements
+    ///
+    /// These requirements cannot be satisfied by `gdbstub` internally, and must
+    /// be handled on a per-target basis.
+    ///
+    /// ### Adjusting PC after a breakpoint is hit
+    ///
+    /// The [GDB remote serial protocol documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Stop-Reply-Packets.html#swbreak-stop-reason)
+    /// notes the following:
+    ///
+    /// > On some architectures, such as x86, at the architecture level, when a
+    /// > breakpoint instruction executes the program counter points at the
+    /// > breakpoint address plus an offset. On such targets, the stub is
+    /// > responsible for adjusting the PC to point back at the breakpoint
+    /// > address.
+    ///
+    /// Omitting PC adjustment may result in unexpected execution flow and/or
+    /// breakpoints not appearing to work correctly.
+    ///
+    /// # Additional Considerations
+    ///
+    /// ### "All-Stop" vs. "Non-Stop" mode
+    ///
+    /// By default, `gdbstub` runs in GDB's
+    /// ["All-Stop" mode](https://sourceware.org/gdb/current/onlinedocs/gdb/All_002dStop-Mode.html),
+    /// whereby _all_ threads should be stopped when returning from `resume`
+    /// (not just the thread associated with the `ThreadStopReason`).
+    ///
+    /// Targets which can stop and resume threads independently of one another
+    /// can also support GDB's
+    /// ["Non-Stop" mode](https://sourceware.org/gdb/current/onlinedocs/gdb/Non_002dStop-Mode.html)
+    /// by implementing the [`MultiThreadNonStop`] extension. When GDB enables
+    /// non-stop mode, `resume` is no longer called, and all execution control
+    /// is routed through the methods on `MultiThreadNonStop` instead.
+    ///
+    /// ### Bare-Metal Targets
+    ///
+    /// On bare-metal targets (such as microcontrollers or emulators), it's
+    /// common to treat individual _CPU cores_ as a separate "threads". e.g:
+    /// in a dual-core system, [CPU0, CPU1] might be mapped to [TID1, TID2]
+    /// (note that TIDs cannot be zero).
+    ///
+    /// In this case, the `Tid` argument of `read/write_addrs` becomes quite
+    /// relevant, as different cores may have different memory maps.
+    fn resume(
+        &mut self,
+        actions: Actions<'_, <Self::Arch as Arch>::Usize>,
+        check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<ThreadStopReason<<Self::Arch as Arch>::Usize>, Self::Error>;
+
+    /// Read the target's registers.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn read_registers(
+        &mut self,
+        regs: &mut <Self::Arch as Arch>::Registers,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Write the target's registers.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn write_registers(
+        &mut self,
+        regs: &<Self::Arch as Arch>::Registers,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Read to a single register on the target.
+    ///
+    /// Implementations should write the value of the register using target's
+    /// native byte order in the buffer `dst`.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn read_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        dst: &mut [u8],
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, dst, tid);
+        Ok(())
+    }
+
+    /// Write from a single register on the target.
+    ///
+    /// The `val` buffer contains the new value of the register in the target's
+    /// native byte order. It is guaranteed to be the exact length as the target
+    /// register.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn write_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        val: &[u8],
+        tid: Tid,
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, val, tid);
+        Ok(())
+    }
+
+    /// Read bytes from the specified address range.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate non-fatal
+    /// error should be returned.
+    fn read_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &mut [u8],
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Write bytes to the specified address range.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate non-fatal
+    /// error should be returned.
+    fn write_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// List all currently active threads.
+    ///
+    /// See [the section above](#bare-metal-targets) on implementing
+    /// thread-related methods on bare-metal (threadless) targets.
+    fn list_active_threads(
+        &mut self,
+        thread_is_active: &mut dyn FnMut(Tid),
+    ) -> Result<(), Self::Error>;
+
+    /// Check if the specified thread is alive.
+    ///
+    /// As a convenience, this method provides a default implementation which
+    /// uses `list_active_threads` to do a linear-search through all active
+    /// threads. On thread-heavy systems, it may be more efficient
+    /// to override this method with a more direct query.
+    fn is_thread_alive(&mut self, tid: Tid) -> Result<bool, Self::Error> {
+        let mut found = false;
+        self.list_active_threads(&mut |active_tid| {
+            if tid == active_tid {
+                found = true;
+            }
+        })?;
+        Ok(found)
+    }
+
+    /// Support for GDB's "Non-Stop" mode.
+    fn support_non_stop(&mut self) -> Option<MultiThreadNonStopOps<Self>> {
+        None
+    }
+
+    /// Support for range stepping (i.e: [`ResumeAction::StepInRange`]).
+    fn support_range_stepping(&mut self) -> Option<MultiThreadRangeSteppingOps<Self>> {
+        None
+    }
+
+    /// Support for reverse stepping (i.e: [`ResumeAction::ReverseStep`]).
+    fn support_reverse_step(&mut self) -> Option<MultiThreadReverseStepOps<Self>> {
+        None
+    }
+
+    /// Support for reverse continue (i.e: [`ResumeAction::ReverseContinue`]).
+    fn support_reverse_cont(&mut self) -> Option<MultiThreadReverseContOps<Self>> {
+        None
+    }
+
+    /// Support for reporting additional information about each thread (e.g:
+    /// thread names).
+    fn support_threads_info(&mut self) -> Option<ThreadsInfoOps<Self>> {
+        None
+    }
+
+    /// Support for reporting a free-form description of each thread's state.
+    fn support_thread_extra_info(&mut self) -> Option<ThreadExtraInfoOps<Self>> {
+        None
+    }
+}
+
+/// Additional information about a thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadInfo<'a> {
+    /// The thread's name (e.g: `"cpu0"`).
+    pub name: Option<&'a [u8]>,
+    /// The processor core the thread was last running on.
+    pub core: Option<usize>,
+    /// A free-form description of the thread's state (e.g: `"Blocked on
+    /// mutex"`).
+    pub extra: Option<&'a [u8]>,
+}
+
+/// Support for reporting additional information about each thread (e.g: a
+/// thread name, which GDB displays alongside the thread's ID in `info
+/// threads`).
+///
+/// When implemented, GDB fetches the thread list using the
+/// `qXfer:threads:read` packet instead of `qfThreadInfo`.
+pub trait ThreadsInfo: MultiThreadOps {
+    /// Report each active thread, along with additional information about
+    /// it, by calling `thread_info` for each thread.
+    ///
+    /// This should report the same threads as
+    /// [`MultiThreadOps::list_active_threads`].
+    fn list_threads_info(
+        &mut self,
+        thread_info: &mut dyn FnMut(Tid, ThreadInfo<'_>),
+    ) -> Result<(), Self::Error>;
+}
+
+define_ext!(ThreadsInfoOps, ThreadsInfo);
+
+/// Support for reporting a free-form description of each thread's state (e.g:
+/// `"Runnable"`, or `"Blocked on mutex 0x1234"`), which GDB displays alongside
+/// the thread's ID in `info threads`.
+///
+/// _Note:_ GDB doesn't use this extension on targets which implement
+/// [`ThreadsInfo`]. Such targets should report the description via
+/// [`ThreadInfo::extra`] instead.
+pub trait ThreadExtraInfo: MultiThreadOps {
+    /// Write a description of the thread `tid` into `buf`, and return the
+    /// number of bytes written.
+    ///
+    /// If the thread doesn't exist, an appropriate non-fatal error should be
+    /// returned.
+    fn thread_extra_info(&mut self, tid: Tid, buf: &mut [u8]) -> TargetResult<usize, Self>;
+}
+
+define_ext!(ThreadExtraInfoOps, ThreadExtraInfo);
+
+/// Support for [`ResumeAction::StepInRange`], whereby the target keeps
+/// stepping a thread until its PC leaves a given address range.
+///
+/// Range stepping allows GDB to step over an entire source line using a single
+/// `vCont` packet, instead of having to issue a packet for each individual
+/// instruction.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiThreadOps::resume` is able to handle `ResumeAction::StepInRange`.
+/// Targets which don't implement this extension will never be sent
+/// `StepInRange` actions.
+pub trait MultiThreadRangeStepping: MultiThreadOps {}
+
+define_ext!(MultiThreadRangeSteppingOps, MultiThreadRangeStepping);
+
+/// Support for [`ResumeAction::ReverseStep`], whereby a thread is stepped
+/// backwards a single instruction.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiThreadOps::resume` is able to handle `ResumeAction::ReverseStep`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseStep` actions.
+///
+/// Once there is no more execution history to step back through, the target
+/// should report a `ReplayLog { pos: ReplayLogPosition::Begin, .. }` stop
+/// reason.
+pub trait MultiThreadReverseStep: MultiThreadOps {}
+
+define_ext!(MultiThreadReverseStepOps, MultiThreadReverseStep);
+
+/// Support for [`ResumeAction::ReverseContinue`], whereby the target is run
+/// backwards until the next event occurs (e.g: a breakpoint is hit).
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `MultiThreadOps::resume` is able to handle `ResumeAction::ReverseContinue`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseContinue` actions.
+///
+/// Once there is no more execution history to run back through, the target
+/// should report a `ReplayLog { pos: ReplayLogPosition::Begin, .. }` stop
+/// reason.
+pub trait MultiThreadReverseCont: MultiThreadOps {}
+
+define_ext!(MultiThreadReverseContOps, MultiThreadReverseCont);
+
+/// Support for GDB's
+/// ["Non-Stop" mode](https://sourceware.org/gdb/current/onlinedocs/gdb/Non_002dStop-Mode.html),
+/// whereby individual threads can be stopped and resumed while other threads
+/// keep running.
+///
+/// Once GDB enables non-stop mode (via the `QNonStop:1` packet), `gdbstub`
+/// stops calling [`MultiThreadOps::resume`], and instead uses the following
+/// methods to drive the target:
+///
+/// - `resume_non_stop` is called whenever GDB sends a `vCont` packet, and
+///   should only _update_ the run-state of the selected threads (without
+///   blocking).
+/// - `poll_non_stop` is called whenever `gdbstub` is waiting for packets from
+///   GDB, and should advance any running threads until one of them stops.
+///
+/// Each stop reason returned from `poll_non_stop` is sent to GDB as an
+/// asynchronous `%Stop` notification. `gdbstub` will not poll the target again
+/// until GDB has acknowledged the notification (via `vStopped`).
+#[allow(clippy::type_complexity)]
+pub trait MultiThreadNonStop: MultiThreadOps {
+    /// Update the run-state of the threads selected by `actions`.
+    ///
+    /// Unlike [`MultiThreadOps::resume`], this method should return
+    /// immediately, leaving any resumed threads running in the background.
+    /// Threads which are not selected by any action should be left in their
+    /// current state (i.e: running threads should keep running).
+    ///
+    /// Actions are listed in priority order: if a thread is selected by
+    /// multiple actions, only the first matching action should be applied.
+    ///
+    /// [`ResumeAction::Stop`] requests that a thread be stopped. Once it has
+    /// stopped, the next call to `poll_non_stop` should report it using
+    /// [`ThreadStopReason::SignalWithThread`] with a `signal` of 0. This
+    /// should happen even if the thread was already stopped.
+    fn resume_non_stop(
+        &mut self,
+        actions: Actions<'_, <Self::Arch as Arch>::Usize>,
+    ) -> Result<(), Self::Error>;
+
+    /// Advance any running threads until one of them stops, returning the
+    /// stop reason associated with that thread.
+    ///
+    /// The `check_gdb_incoming` callback returns `true` once GDB has sent data
+    /// which `gdbstub` must process (e.g: a new packet). When this happens, or
+    /// if there are no running threads (or pending stop events) left to
+    /// report, this method should return `Ok(None)` as soon as possible.
+    ///
+    /// Every stop reason must identify the thread which stopped. As such,
+    /// [`ThreadStopReason::DoneStep`] and [`ThreadStopReason::Signal`] are
+    /// not supported in non-stop mode: a completed step should be reported
+    /// using [`ThreadStopReason::SignalWithThread`] with a `signal` of 5
+    /// (i.e: `SIGTRAP`) instead.
+    fn poll_non_stop(
+        &mut self,
+        check_gdb_incoming: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<ThreadStopReason<<Self::Arch as Arch>::Usize>>, Self::Error>;
+
+    /// Check if the thread `tid` is currently stopped.
+    ///
+    /// When GDB asks for the target's state (e.g: after connecting), only
+    /// stopped threads are reported.
+    fn is_thread_stopped(&mut self, tid: Tid) -> Result<bool, Self::Error>;
+}
+
+define_ext!(MultiThreadNonStopOps, MultiThreadNonStop);
+
+/// Describes why a thread stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ThreadStopReason<U> {
+    /// Completed the single-step request.
+    DoneStep,
+    /// `check_gdb_interrupt` returned `true`
+    GdbInterrupt,
+    /// Halted
+    Halted,
+    /// A thread hit a software breakpoint (e.g. due to a trap instruction).
+    ///
+    /// NOTE: This does not necessarily have to be a breakpoint configured by
+    /// the client/user of the current GDB session.
+    SwBreak(Tid),
+    /// A thread hit a hardware breakpoint.
+    HwBreak(Tid),
+    /// A thread hit a watchpoint.
+    Watch {
+        /// Which thread hit the watchpoint
+        tid: Tid,
+        /// Kind of watchpoint that was hit
+        kind: WatchKind,
+        /// Address of watched memory
+        addr: U,
+    },
+    /// The program received a signal
+    Signal(u8),
+    /// A specific thread received a signal.
+    ///
+    /// When running in non-stop mode, a `signal` of 0 indicates that the
+    /// thread was stopped in response to a [`ResumeAction::Stop`] request.
+    SignalWithThread {
+        /// Which thread received the signal
+        tid: Tid,
+        /// Signal number
+        signal: u8,
+    },
+    /// A thread requested that GDB perform a system call on it's behalf.
+    ///
+    /// Requires implementing the
+    /// [`FileIo`](crate::target::ext::file_io::FileIo) extension, which is used
+    /// to deliver the syscall's result back to the target.
+    SyscallRequest {
+        /// Which thread made the request
+        tid: Tid,
+        /// The requested syscall
+        syscall: Syscall<U>,
+    },
+    /// Reached the beginning (or end) of the execution history while
+    /// executing in reverse, and there is no more history to replay.
+    ReplayLog {
+        /// Which thread reached the end of the execution history
+        tid: Tid,
+        /// Which end of the execution history was reached
+        pos: ReplayLogPosition,
+    },
+}
+
+/// An iterator of `(TidSelector, ResumeAction)` used to specify how threads
+/// should be resumed when running in multi threaded mode. It is _guaranteed_ to
+/// contain at least one action.
+///
+/// See the documentation for
+/// [`Target::resume`](trait.Target.html#tymethod.resume) for more details.
+pub struct Actions<'a, U> {
+    inner: &'a mut dyn Iterator<Item = (TidSelector, ResumeAction<U>)>,
+}
+
+impl<U> core::fmt::Debug for Actions<'_, U> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Actions {{ .. }}")
+    }
+}
+
+impl<U> Actions<'_, U> {
+    pub(crate) fn new(
+        iter: &mut dyn Iterator<Item = (TidSelector, ResumeAction<U>)>,
+    ) -> Actions<'_, U> {
+        Actions { inner: iter }
+    }
+}
+
+impl<U> Iterator for Actions<'_, U> {
+    type Item = (TidSelector, ResumeAction<U>);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
@@ -0,0 +1,220 @@
+//! Base debugging operations for single threaded targets.
+
+use crate::arch::Arch;
+use crate::target::ext::breakpoints::WatchKind;
+use crate::target::ext::file_io::Syscall;
+use crate::target::{Target, TargetResult};
+
+// Convenient re-exports
+pub use super::{ReplayLogPosition, ResumeAction};
+
+/// Base debugging operations for single threaded targets.
+#[allow(clippy::type_complexity)]
+pub trait SingleThreadOps: Target {
+    /// Resume execution on the target.
+    ///
+    /// `action` specifies how the target should be resumed (i.e:
+    /// single-step vs. full continue).
+    ///
+    /// The `check_gdb_interrupt` callback can be invoked to check if GDB sent
+    /// an Interrupt packet (i.e: the user pressed Ctrl-C). It's recommended to
+    /// invoke this callback every-so-often while the system is running (e.g:
+    /// every X cycles/milliseconds). Periodically checking for incoming
+    /// interrupt packets is _not_ required, but it is _recommended_.
+    ///
+    /// # Implementation requirements
+    ///
+    /// These requirements cannot be satisfied by `gdbstub` internally, and must
+    /// be handled on a per-target basis.
+    ///
+    /// ### Adjusting PC after a breakpoint is hit
+    ///
+    /// The [GDB remote serial protocol documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Stop-Reply-Packets.html#swbreak-stop-reason)
+    /// notes the following:
+    ///
+    /// > On some architectures, such as x86, at the architecture level, when a
+    /// > breakpoint instruction executes the program counter points at the
+    /// > breakpoint address plus an offset. On such targets, the stub is
+    /// > responsible for adjusting the PC to point back at the breakpoint
+    /// > address.
+    ///
+    /// Omitting PC adjustment may result in unexpected execution flow and/or
+    /// breakpoints not appearing to work correctly.
+    fn resume(
+        &mut self,
+        action: ResumeAction<<Self::Arch as Arch>::Usize>,
+        check_gdb_interrupt: &mut dyn FnMut() -> bool,
+    ) -> Result<StopReason<<Self::Arch as Arch>::Usize>, Self::Error>;
+
+    /// Read the target's registers.
+    fn read_registers(
+        &mut self,
+        regs: &mut <Self::Arch as Arch>::Registers,
+    ) -> TargetResult<(), Self>;
+
+    /// Write the target's registers.
+    fn write_registers(&mut self, regs: &<Self::Arch as Arch>::Registers)
+        -> TargetResult<(), Self>;
+
+    /// Read to a single register on the target.
+    ///
+    /// Implementations should write the value of the register using target's
+    /// native byte order in the buffer `dst`.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn read_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        dst: &mut [u8],
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, dst);
+        Ok(())
+    }
+
+    /// Write from a single register on the target.
+    ///
+    /// The `val` buffer contains the new value of the register in the target's
+    /// native byte order. It is guaranteed to be the exact length as the target
+    /// register.
+    ///
+    /// If the requested register could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    ///
+    /// _Note:_ This method includes a stubbed default implementation which
+    /// simply returns `Ok(())`. This is due to the fact that several built-in
+    /// `arch` implementations haven't been updated with proper `RegId`
+    /// implementations.
+    fn write_register(
+        &mut self,
+        reg_id: <Self::Arch as Arch>::RegId,
+        val: &[u8],
+    ) -> TargetResult<(), Self> {
+        let _ = (reg_id, val);
+        Ok(())
+    }
+
+    /// Read bytes from the specified address range.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate
+    /// non-fatal error should be returned.
+    fn read_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &mut [u8],
+    ) -> TargetResult<(), Self>;
+
+    /// Write bytes to the specified address range.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate
+    /// non-fatal error should be returned.
+    fn write_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+    ) -> TargetResult<(), Self>;
+
+    /// Support for range stepping (i.e: [`ResumeAction::StepInRange`]).
+    fn support_range_stepping(&mut self) -> Option<SingleThreadRangeSteppingOps<Self>> {
+        None
+    }
+
+    /// Support for reverse stepping (i.e: [`ResumeAction::ReverseStep`]).
+    fn support_reverse_step(&mut self) -> Option<SingleThreadReverseStepOps<Self>> {
+        None
+    }
+
+    /// Support for reverse continue (i.e: [`ResumeAction::ReverseContinue`]).
+    fn support_reverse_cont(&mut self) -> Option<SingleThreadReverseContOps<Self>> {
+        None
+    }
+}
+
+/// Support for [`ResumeAction::StepInRange`], whereby the target keeps
+/// stepping until the PC leaves a given address range.
+///
+/// Range stepping allows GDB to step over an entire source line using a single
+/// `vCont` packet, instead of having to issue a packet for each individual
+/// instruction.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `SingleThreadOps::resume` is able to handle `ResumeAction::StepInRange`.
+/// Targets which don't implement this extension will never be sent
+/// `StepInRange` actions.
+pub trait SingleThreadRangeStepping: SingleThreadOps {}
+
+define_ext!(SingleThreadRangeSteppingOps, SingleThreadRangeStepping);
+
+/// Support for [`ResumeAction::ReverseStep`], whereby the target is stepped
+/// backwards a single instruction.
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `SingleThreadOps::resume` is able to handle `ResumeAction::ReverseStep`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseStep` actions.
+///
+/// Once there is no more execution history to step back through, the target
+/// should report a `ReplayLog(ReplayLogPosition::Begin)` stop reason.
+pub trait SingleThreadReverseStep: SingleThreadOps {}
+
+define_ext!(SingleThreadReverseStepOps, SingleThreadReverseStep);
+
+/// Support for [`ResumeAction::ReverseContinue`], whereby the target is run
+/// backwards until the next event occurs (e.g: a breakpoint is hit).
+///
+/// This trait doesn't have any methods: implementing it simply signals that
+/// `SingleThreadOps::resume` is able to handle `ResumeAction::ReverseContinue`.
+/// Targets which don't implement this extension will never be sent
+/// `ReverseContinue` actions.
+///
+/// Once there is no more execution history to run back through, the target
+/// should report a `ReplayLog(ReplayLogPosition::Begin)` stop reason.
+pub trait SingleThreadReverseCont: SingleThreadOps {}
+
+define_ext!(SingleThreadReverseContOps, SingleThreadReverseCont);
+
+/// Describes why the target stopped.
+// NOTE: This is a simplified version of `multithread::ThreadStopReason` that omits any references
+// to Tid or threads. Internally, it is converted into multithread::ThreadStopReason.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StopReason<U> {
+    /// Completed the single-step request.
+    DoneStep,
+    /// `check_gdb_interrupt` returned `true`
+    GdbInterrupt,
+    /// Halted
+    Halted,
+    /// Hit a software breakpoint (e.g. due to a trap instruction).
+    ///
+    /// NOTE: This does not necessarily have to be a breakpoint configured by
+    /// the client/user of the current GDB session.
+    SwBreak,
+    /// Hit a hardware breakpoint.
+    HwBreak,
+    /// Hit a watchpoint.
+    Watch {
+        /// Kind of watchpoint that was hit
+        kind: WatchKind,
+        /// Address of watched memory
+        addr: U,
+    },
+    /// The program received a signal
+    Signal(u8),
+    /// The target requested that GDB perform a system call on it's behalf.
+    ///
+    /// Requires implementing the
+    /// [`FileIo`](crate::target::ext::file_io::FileIo) extension, which is used
+    /// to deliver the syscall's result back to the target.
+    SyscallRequest(Syscall<U>),
+    /// Reached the beginning (or end) of the execution history while
+    /// executing in reverse, and there is no more history to replay.
+    ReplayLog(ReplayLogPosition),
+}
@@ -0,0 +1,99 @@
+//! Record the branches taken by a thread, enabling GDB's `record btrace`
+//! functionality (e.g: `info record`, `record instruction-history`, and
+//! `record function-call-history`).
+//!
+//! Branch traces are reported in the Branch Trace Store (BTS) format, whereby
+//! a thread's execution history is described as a list of blocks of
+//! sequentially executed instructions. Each block begins at the target of a
+//! taken branch, and ends at the next taken branch.
+//!
+//! _Note:_ The Intel Processor Trace (PT) format is not currently supported.
+//!
+//! # Reading the trace
+//!
+//! GDB may read the trace in multiple chunks, so `gdbstub` splits each read
+//! into two steps:
+//!
+//! 1. [`BranchTrace::capture_btrace`] is called once at the start of each
+//!    read, and should take a "snapshot" of the blocks to report (based on
+//!    the requested [`BtraceReadType`]).
+//! 2. [`BranchTrace::read_btrace`] is then called once per chunk, and should
+//!    report the blocks from the most recent snapshot.
+
+use crate::arch::Arch;
+use crate::common::{Pid, Tid};
+use crate::target::{Target, TargetResult};
+
+/// Which parts of the branch trace GDB is requesting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BtraceReadType {
+    /// The entire trace.
+    All,
+    /// The entire trace, but only if the thread has executed any
+    /// instructions since the previous read. Otherwise, no blocks should be
+    /// reported.
+    New,
+    /// Only the part of the trace which was recorded since the previous read.
+    ///
+    /// The oldest block in the delta should be the block which was still
+    /// being executed at the time of the previous read.
+    ///
+    /// If a delta cannot be computed (e.g: due to the trace buffer
+    /// overflowing), a non-fatal error should be returned, in which case GDB
+    /// will re-read the trace using `BtraceReadType::New`.
+    Delta,
+}
+
+/// The branch trace configuration of a thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BtsConfig {
+    /// The size of the thread's trace buffer (in bytes), if known.
+    pub size: Option<u64>,
+}
+
+/// Target Extension - Record the branches taken by a thread.
+///
+/// All methods operate on the thread `tid` (in process `pid`). On targets
+/// which don't implement the multiprocess API, `pid` is always set to an
+/// arbitrary placeholder value, and should be ignored.
+///
+/// See the [module level documentation](index.html) for more details.
+#[allow(clippy::type_complexity)]
+pub trait BranchTrace: Target {
+    /// Start recording the thread's branches.
+    fn enable_btrace(&mut self, pid: Pid, tid: Tid) -> TargetResult<(), Self>;
+
+    /// Stop recording the thread's branches, and discard any recorded trace.
+    fn disable_btrace(&mut self, pid: Pid, tid: Tid) -> TargetResult<(), Self>;
+
+    /// Return the thread's branch trace configuration, or `None` if its
+    /// branches aren't being recorded.
+    fn btrace_config(&mut self, pid: Pid, tid: Tid) -> TargetResult<Option<BtsConfig>, Self>;
+
+    /// Take a snapshot of the thread's branch trace, to be reported by
+    /// subsequent calls to `read_btrace`.
+    ///
+    /// The snapshot should include the block the thread is currently
+    /// executing (i.e: ending at the thread's current PC).
+    fn capture_btrace(
+        &mut self,
+        pid: Pid,
+        tid: Tid,
+        read_type: BtraceReadType,
+    ) -> TargetResult<(), Self>;
+
+    /// Report each block in the most recent snapshot of the thread's branch
+    /// trace via the provided callback, starting with the _newest_ block.
+    ///
+    /// Each block is reported as a `(begin, end)` pair, where `begin` is the
+    /// address of the block's first instruction, and `end` is the address of
+    /// the block's last instruction.
+    fn read_btrace(
+        &mut self,
+        pid: Pid,
+        tid: Tid,
+        register_block: &mut dyn FnMut(<Self::Arch as Arch>::Usize, <Self::Arch as Arch>::Usize),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(BranchTraceOps, BranchTrace);
@@ -0,0 +1,21 @@
+//! Provide the path of the executable file a process is running.
+//!
+//! GDB uses this to automatically load the executable's symbols after
+//! attaching to a process (e.g: via `attach <pid>` in extended mode), instead
+//! of requiring the user to specify it manually via the `file` command.
+
+use crate::common::Pid;
+use crate::target::{Target, TargetResult};
+
+/// Target Extension - Provide the path of the executable file a process is
+/// running.
+pub trait ExecFile: Target {
+    /// Return the absolute path of the executable file being run by the
+    /// process `pid`.
+    ///
+    /// On targets which don't implement the multiprocess API, `pid` is always
+    /// set to an arbitrary placeholder value, and should be ignored.
+    fn get_exec_file(&mut self, pid: Pid) -> TargetResult<&[u8], Self>;
+}
+
+define_ext!(ExecFileOps, ExecFile);
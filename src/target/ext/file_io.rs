@@ -0,0 +1,181 @@
+//! Perform system calls on the GDB host via the File-I/O protocol extension.
+//!
+//! The File-I/O extension allows targets without an operating system (e.g:
+//! bare-metal programs running in an emulator) to access files on the host
+//! running GDB, as well as read/write from the GDB console.
+//!
+//! Targets request a system call by stopping with a `SyscallRequest` stop
+//! reason (e.g:
+//! [`StopReason::SyscallRequest`](super::base::singlethread::StopReason::SyscallRequest)).
+//! `gdbstub` forwards the request to GDB, which performs the system call on
+//! the host (reading / writing target memory as required), and then replies
+//! with the syscall's result. The result is delivered to the target via
+//! [`FileIo::complete_syscall`], after which the target is resumed using the
+//! same resume action(s) which led to the syscall request. Without the
+//! `alloc` feature, only the first two resume actions can be saved, which is
+//! enough for the `vCont` packets GDB sends in all-stop mode.
+//!
+//! Pointers to strings (e.g: the `path` passed to `open`) are specified
+//! alongside the string's length, which _must_ include the trailing null
+//! terminator.
+//!
+//! _Note:_ GDB doesn't support syscall requests in non-stop mode. Any
+//! requests made while running in non-stop mode are immediately failed with
+//! `EUNKNOWN` (via [`FileIo::complete_syscall`]), and the requesting thread
+//! should carry on running as before.
+//!
+//! See the [GDB documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/File_002dI_002fO-Remote-Protocol-Extension.html)
+//! for more details.
+
+use crate::target::ext::host_io::{HostIoErrno, HostIoOpenFlags, HostIoOpenMode};
+use crate::target::{Target, TargetResult};
+
+/// The reference point of an `lseek` offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekWhence {
+    /// Offset from the start of the file.
+    Set = 0,
+    /// Offset from the current file position.
+    Cur = 1,
+    /// Offset from the end of the file.
+    End = 2,
+}
+
+/// A system call to perform on the GDB host.
+///
+/// File descriptors 0, 1, and 2 correspond to the GDB console's `stdin`,
+/// `stdout`, and `stderr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syscall<U> {
+    /// `int open(const char *path, int flags, mode_t mode)`
+    Open {
+        /// Pointer to the path.
+        path: U,
+        /// Length of the path (including the null terminator).
+        path_len: U,
+        /// Open flags.
+        flags: HostIoOpenFlags,
+        /// Mode bits (used when creating a new file).
+        mode: HostIoOpenMode,
+    },
+    /// `int close(int fd)`
+    Close {
+        /// File descriptor.
+        fd: u32,
+    },
+    /// `int read(int fd, void *buf, unsigned int count)`
+    Read {
+        /// File descriptor.
+        fd: u32,
+        /// Pointer to the buffer to read into.
+        buf: U,
+        /// Number of bytes to read.
+        count: U,
+    },
+    /// `int write(int fd, const void *buf, unsigned int count)`
+    Write {
+        /// File descriptor.
+        fd: u32,
+        /// Pointer to the data to write.
+        buf: U,
+        /// Number of bytes to write.
+        count: U,
+    },
+    /// `long lseek(int fd, long offset, int whence)`
+    Lseek {
+        /// File descriptor.
+        fd: u32,
+        /// Offset (relative to `whence`).
+        offset: i64,
+        /// Reference point of the offset.
+        whence: SeekWhence,
+    },
+    /// `int rename(const char *oldpath, const char *newpath)`
+    Rename {
+        /// Pointer to the old path.
+        old_path: U,
+        /// Length of the old path (including the null terminator).
+        old_path_len: U,
+        /// Pointer to the new path.
+        new_path: U,
+        /// Length of the new path (including the null terminator).
+        new_path_len: U,
+    },
+    /// `int unlink(const char *path)`
+    Unlink {
+        /// Pointer to the path.
+        path: U,
+        /// Length of the path (including the null terminator).
+        path_len: U,
+    },
+    /// `int stat(const char *path, struct stat *buf)`
+    ///
+    /// GDB writes back the protocol's fixed-size `struct stat` (see
+    /// [`HostIoStat`](super::host_io::HostIoStat)).
+    Stat {
+        /// Pointer to the path.
+        path: U,
+        /// Length of the path (including the null terminator).
+        path_len: U,
+        /// Pointer to the `struct stat` to fill in.
+        buf: U,
+    },
+    /// `int fstat(int fd, struct stat *buf)`
+    ///
+    /// GDB writes back the protocol's fixed-size `struct stat` (see
+    /// [`HostIoStat`](super::host_io::HostIoStat)).
+    Fstat {
+        /// File descriptor.
+        fd: u32,
+        /// Pointer to the `struct stat` to fill in.
+        buf: U,
+    },
+    /// `int gettimeofday(struct timeval *tv, void *tz)`
+    GetTimeOfDay {
+        /// Pointer to the `struct timeval` to fill in.
+        tv: U,
+        /// Pointer to the timezone (unused, and should be null).
+        tz: U,
+    },
+    /// `int isatty(int fd)`
+    IsATty {
+        /// File descriptor.
+        fd: u32,
+    },
+    /// `int system(const char *command)`
+    ///
+    /// _Note:_ GDB refuses to run commands unless the user explicitly enables
+    /// it via `set remote system-call-allowed 1`.
+    System {
+        /// Pointer to the command.
+        command: U,
+        /// Length of the command (including the null terminator).
+        command_len: U,
+    },
+}
+
+/// The result of a system call performed by GDB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyscallResult {
+    /// The syscall's return value (`-1` on error).
+    pub retcode: i64,
+    /// The errno value set by the syscall (only reported on error).
+    pub errno: Option<HostIoErrno>,
+    /// The user pressed Ctrl-C while the syscall was being performed.
+    ///
+    /// Once the result is delivered, `gdbstub` reports the target as having
+    /// stopped with `SIGINT` (instead of resuming it). If `errno` is
+    /// `EINTR`, the syscall was _not_ performed, and should be retried once
+    /// the target is resumed. Otherwise, the syscall completed before the
+    /// interrupt arrived.
+    pub interrupted: bool,
+}
+
+/// Target Extension - Perform system calls on the GDB host.
+pub trait FileIo: Target {
+    /// Deliver the result of the most recently requested syscall to the
+    /// target.
+    fn complete_syscall(&mut self, result: SyscallResult) -> TargetResult<(), Self>;
+}
+
+define_ext!(FileIoOps, FileIo);
@@ -0,0 +1,70 @@
+//! Program flash memory on the target.
+//!
+//! Flash memory typically can't be written to using plain memory writes.
+//! Instead, GDB uses a dedicated set of flash programming packets whenever
+//! writing to a region of memory marked as `flash` in the target's
+//! [memory map](super::memory_map) (e.g: when running `load`).
+//!
+//! A flash programming session is made up of a series of
+//! [`flash_erase`](Flash::flash_erase) and [`flash_write`](Flash::flash_write)
+//! operations, followed by a final call to [`flash_done`](Flash::flash_done).
+//! Targets may choose to buffer writes until `flash_done` is called, at which
+//! point the new flash contents must be committed.
+//!
+//! See the [GDB documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Packets.html#vFlashErase-packet)
+//! for more details.
+
+use crate::arch::Arch;
+use crate::target::{Target, TargetError, TargetResult};
+
+/// An error which may occur when writing to flash.
+pub enum FlashWriteError<E> {
+    /// The address range doesn't correspond to a flash region (reported to
+    /// GDB as `E.memtype`).
+    NotFlash,
+    /// Any other error.
+    Target(TargetError<E>),
+}
+
+impl<E> From<TargetError<E>> for FlashWriteError<E> {
+    fn from(e: TargetError<E>) -> FlashWriteError<E> {
+        FlashWriteError::Target(e)
+    }
+}
+
+/// Converts a `()` into a `TargetError::NonFatal`.
+impl<E> From<()> for FlashWriteError<E> {
+    fn from(_: ()) -> FlashWriteError<E> {
+        FlashWriteError::Target(TargetError::NonFatal)
+    }
+}
+
+/// Target Extension - Program flash memory.
+pub trait Flash: Target {
+    /// Erase `length` bytes of flash, starting at `start_addr`.
+    ///
+    /// GDB ensures that the region is aligned to the flash's block size (as
+    /// specified in the target's memory map).
+    fn flash_erase(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        length: <Self::Arch as Arch>::Usize,
+    ) -> TargetResult<(), Self>;
+
+    /// Write `data` to a previously erased region of flash, starting at
+    /// `start_addr`.
+    ///
+    /// Writes are not guaranteed to be visible in memory until
+    /// [`flash_done`](Flash::flash_done) is called.
+    fn flash_write(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+    ) -> Result<(), FlashWriteError<Self::Error>>;
+
+    /// Indicate that flash programming is complete, and that any buffered
+    /// writes should be committed to flash.
+    fn flash_done(&mut self) -> TargetResult<(), Self>;
+}
+
+define_ext!(FlashOps, Flash);
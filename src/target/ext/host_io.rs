@@ -0,0 +1,424 @@
+//! Provide Host I/O operations for the target.
+//!
+//! GDB uses Host I/O (i.e: the `vFile` family of packets) to access files on
+//! the remote system. For example, when using a `target:` sysroot, GDB will use
+//! Host I/O to fetch the target's executable and shared libraries, and the
+//! `remote get` / `remote put` / `remote delete` commands use Host I/O to
+//! transfer files to and from the target.
+//!
+//! Each Host I/O operation is exposed as it's own sub-extension, enabling
+//! targets to only implement the operations that make sense for their
+//! particular platform (e.g: read-only filesystems can omit `pwrite` and
+//! `unlink`).
+//!
+//! _Note:_ The various constants and data structures defined in this module
+//! (e.g: open flags, mode bits, errno values, `struct stat`) follow the GDB
+//! File-I/O protocol conventions, and may not match the host platform's
+//! native values. Targets are responsible for translating between the two.
+//!
+//! See the [GDB documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Host-I_002fO-Packets.html)
+//! for more details.
+
+use crate::common::Pid;
+use crate::target::Target;
+
+/// Flags passed to the `open` operation.
+///
+/// The access mode (i.e: `O_RDONLY`, `O_WRONLY`, or `O_RDWR`) is stored in the
+/// lowest 2 bits of the flags, and can be retrieved using
+/// [`access_mode`](HostIoOpenFlags::access_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostIoOpenFlags(pub u32);
+
+impl HostIoOpenFlags {
+    /// Open for reading only.
+    pub const O_RDONLY: u32 = 0x0;
+    /// Open for writing only.
+    pub const O_WRONLY: u32 = 0x1;
+    /// Open for reading and writing.
+    pub const O_RDWR: u32 = 0x2;
+    /// Append to the end of the file on each write.
+    pub const O_APPEND: u32 = 0x8;
+    /// Create the file if it doesn't exist.
+    pub const O_CREAT: u32 = 0x200;
+    /// Truncate the file to zero length.
+    pub const O_TRUNC: u32 = 0x400;
+    /// Fail if the file already exists (when used with `O_CREAT`).
+    pub const O_EXCL: u32 = 0x800;
+
+    /// Returns the access mode (one of `O_RDONLY`, `O_WRONLY`, or `O_RDWR`).
+    pub fn access_mode(self) -> u32 {
+        self.0 & 0x3
+    }
+
+    /// Check if the specified flag (e.g: `O_CREAT`) is set.
+    pub fn contains(self, flag: u32) -> bool {
+        self.0 & flag == flag
+    }
+}
+
+/// File mode bits, as used by the `open` operation and `struct stat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostIoOpenMode(pub u32);
+
+impl HostIoOpenMode {
+    /// A regular file.
+    pub const S_IFREG: u32 = 0o100000;
+    /// A directory.
+    pub const S_IFDIR: u32 = 0o40000;
+    /// Read permission, owner.
+    pub const S_IRUSR: u32 = 0o400;
+    /// Write permission, owner.
+    pub const S_IWUSR: u32 = 0o200;
+    /// Execute permission, owner.
+    pub const S_IXUSR: u32 = 0o100;
+    /// Read permission, group.
+    pub const S_IRGRP: u32 = 0o40;
+    /// Write permission, group.
+    pub const S_IWGRP: u32 = 0o20;
+    /// Execute permission, group.
+    pub const S_IXGRP: u32 = 0o10;
+    /// Read permission, others.
+    pub const S_IROTH: u32 = 0o4;
+    /// Write permission, others.
+    pub const S_IWOTH: u32 = 0o2;
+    /// Execute permission, others.
+    pub const S_IXOTH: u32 = 0o1;
+
+    /// Check if the specified mode bit (e.g: `S_IRUSR`) is set.
+    pub fn contains(self, bit: u32) -> bool {
+        self.0 & bit == bit
+    }
+}
+
+/// Errno values for Host I/O operations, as defined by the GDB File-I/O
+/// protocol.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostIoErrno {
+    /// Operation not permitted.
+    EPERM = 1,
+    /// No such file or directory.
+    ENOENT = 2,
+    /// Interrupted system call.
+    EINTR = 4,
+    /// Bad file number.
+    EBADF = 9,
+    /// Permission denied.
+    EACCES = 13,
+    /// Bad address.
+    EFAULT = 14,
+    /// Device or resource busy.
+    EBUSY = 16,
+    /// File exists.
+    EEXIST = 17,
+    /// No such device.
+    ENODEV = 19,
+    /// Not a directory.
+    ENOTDIR = 20,
+    /// Is a directory.
+    EISDIR = 21,
+    /// Invalid argument.
+    EINVAL = 22,
+    /// File table overflow.
+    ENFILE = 23,
+    /// Too many open files.
+    EMFILE = 24,
+    /// File too large.
+    EFBIG = 27,
+    /// No space left on device.
+    ENOSPC = 28,
+    /// Illegal seek.
+    ESPIPE = 29,
+    /// Read-only file system.
+    EROFS = 30,
+    /// File name too long.
+    ENAMETOOLONG = 91,
+    /// Unknown errno.
+    EUNKNOWN = 9999,
+}
+
+impl HostIoErrno {
+    /// Convert a raw errno value (as sent by GDB) into a `HostIoErrno`,
+    /// falling back to `EUNKNOWN` for unrecognized values.
+    pub(crate) fn from_raw(errno: u32) -> HostIoErrno {
+        use HostIoErrno::*;
+        match errno {
+            1 => EPERM,
+            2 => ENOENT,
+            4 => EINTR,
+            9 => EBADF,
+            13 => EACCES,
+            14 => EFAULT,
+            16 => EBUSY,
+            17 => EEXIST,
+            19 => ENODEV,
+            20 => ENOTDIR,
+            21 => EISDIR,
+            22 => EINVAL,
+            23 => ENFILE,
+            24 => EMFILE,
+            27 => EFBIG,
+            28 => ENOSPC,
+            29 => ESPIPE,
+            30 => EROFS,
+            91 => ENAMETOOLONG,
+            _ => EUNKNOWN,
+        }
+    }
+}
+
+/// An error which may occur during a Host I/O operation.
+#[derive(Debug)]
+pub enum HostIoError<E> {
+    /// An operation-specific non-fatal error, which is reported to GDB using
+    /// the specified errno.
+    Errno(HostIoErrno),
+    /// A target-specific fatal error.
+    ///
+    /// **WARNING:** Returning this error will immediately halt the target's
+    /// execution and return a `GdbStubError::TargetError` from `GdbStub::run`!
+    Fatal(E),
+}
+
+impl<E> From<HostIoErrno> for HostIoError<E> {
+    fn from(errno: HostIoErrno) -> HostIoError<E> {
+        HostIoError::Errno(errno)
+    }
+}
+
+/// Converts a `std::io::Error` into a `HostIoError::Errno`.
+#[cfg(feature = "std")]
+impl<E> From<std::io::Error> for HostIoError<E> {
+    fn from(e: std::io::Error) -> HostIoError<E> {
+        use std::io::ErrorKind::*;
+        let errno = match e.kind() {
+            PermissionDenied => HostIoErrno::EACCES,
+            NotFound => HostIoErrno::ENOENT,
+            Interrupted => HostIoErrno::EINTR,
+            AlreadyExists => HostIoErrno::EEXIST,
+            InvalidInput => HostIoErrno::EINVAL,
+            _ => HostIoErrno::EUNKNOWN,
+        };
+        HostIoError::Errno(errno)
+    }
+}
+
+/// A specialized `Result` type for Host I/O operations. Supports reporting
+/// non-fatal errors back to the GDB client.
+///
+/// See [`HostIoError`] for more details.
+pub type HostIoResult<T, Tgt> = Result<T, HostIoError<<Tgt as Target>::Error>>;
+
+/// The data returned by the `fstat` operation, encoded by `gdbstub` using
+/// GDB's `struct stat` layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostIoStat {
+    /// The device.
+    pub st_dev: u32,
+    /// The inode.
+    pub st_ino: u32,
+    /// Protection bits.
+    pub st_mode: HostIoOpenMode,
+    /// The number of hard links.
+    pub st_nlink: u32,
+    /// The user id of the owner.
+    pub st_uid: u32,
+    /// The group id of the owner.
+    pub st_gid: u32,
+    /// The device type, if the file is an inode device.
+    pub st_rdev: u32,
+    /// The size of the file in bytes.
+    pub st_size: u64,
+    /// The blocksize for the filesystem.
+    pub st_blksize: u64,
+    /// The number of blocks allocated.
+    pub st_blocks: u64,
+    /// The last time the file was accessed, in seconds since the epoch.
+    pub st_atime: u32,
+    /// The last time the file was modified, in seconds since the epoch.
+    pub st_mtime: u32,
+    /// The last time the file was changed, in seconds since the epoch.
+    pub st_ctime: u32,
+}
+
+impl HostIoStat {
+    /// The size of GDB's `struct stat`, in bytes.
+    pub(crate) const SIZE: usize = 0x40;
+
+    /// Encode the struct using GDB's `struct stat` layout (big-endian, with
+    /// fixed-size fields).
+    pub(crate) fn to_be_bytes(self) -> [u8; Self::SIZE] {
+        let mut buf = [0; Self::SIZE];
+        let fields: [&[u8]; 13] = [
+            &self.st_dev.to_be_bytes(),
+            &self.st_ino.to_be_bytes(),
+            &self.st_mode.0.to_be_bytes(),
+            &self.st_nlink.to_be_bytes(),
+            &self.st_uid.to_be_bytes(),
+            &self.st_gid.to_be_bytes(),
+            &self.st_rdev.to_be_bytes(),
+            &self.st_size.to_be_bytes(),
+            &self.st_blksize.to_be_bytes(),
+            &self.st_blocks.to_be_bytes(),
+            &self.st_atime.to_be_bytes(),
+            &self.st_mtime.to_be_bytes(),
+            &self.st_ctime.to_be_bytes(),
+        ];
+
+        let mut i = 0;
+        for field in fields.iter() {
+            buf[i..i + field.len()].copy_from_slice(field);
+            i += field.len();
+        }
+        buf
+    }
+}
+
+/// Select the filesystem Host I/O operations should act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsKind {
+    /// The filesystem as seen by the remote stub.
+    Stub,
+    /// The filesystem as seen by the specified process.
+    Pid(Pid),
+}
+
+/// Target Extension - Perform I/O operations on the target's filesystem.
+///
+/// See the [host_io module documentation](index.html).
+pub trait HostIo: Target {
+    /// Enable open operation.
+    fn support_open(&mut self) -> Option<HostIoOpenOps<Self>> {
+        None
+    }
+
+    /// Enable close operation.
+    fn support_close(&mut self) -> Option<HostIoCloseOps<Self>> {
+        None
+    }
+
+    /// Enable pread operation.
+    fn support_pread(&mut self) -> Option<HostIoPreadOps<Self>> {
+        None
+    }
+
+    /// Enable pwrite operation.
+    fn support_pwrite(&mut self) -> Option<HostIoPwriteOps<Self>> {
+        None
+    }
+
+    /// Enable fstat operation.
+    fn support_fstat(&mut self) -> Option<HostIoFstatOps<Self>> {
+        None
+    }
+
+    /// Enable unlink operation.
+    fn support_unlink(&mut self) -> Option<HostIoUnlinkOps<Self>> {
+        None
+    }
+
+    /// Enable readlink operation.
+    fn support_readlink(&mut self) -> Option<HostIoReadlinkOps<Self>> {
+        None
+    }
+
+    /// Enable setfs operation.
+    fn support_setfs(&mut self) -> Option<HostIoSetfsOps<Self>> {
+        None
+    }
+}
+
+define_ext!(HostIoOps, HostIo);
+
+/// Nested Target Extension - Host I/O open operation.
+pub trait HostIoOpen: HostIo {
+    /// Open a file at `filename` and return a file descriptor for it.
+    ///
+    /// `mode` is only meaningful if the file is being created.
+    fn open(
+        &mut self,
+        filename: &[u8],
+        flags: HostIoOpenFlags,
+        mode: HostIoOpenMode,
+    ) -> HostIoResult<u32, Self>;
+}
+
+define_ext!(HostIoOpenOps, HostIoOpen);
+
+/// Nested Target Extension - Host I/O close operation.
+pub trait HostIoClose: HostIo {
+    /// Close the open file corresponding to `fd`.
+    fn close(&mut self, fd: u32) -> HostIoResult<(), Self>;
+}
+
+define_ext!(HostIoCloseOps, HostIoClose);
+
+/// Nested Target Extension - Host I/O pread operation.
+pub trait HostIoPread: HostIo {
+    /// Read up to `count` bytes from the file corresponding to `fd`, starting
+    /// at `offset` bytes into the file, and return the number of bytes read.
+    ///
+    /// Data should be written into `buf`, which is guaranteed to be at least
+    /// `count` bytes long.
+    ///
+    /// _Note:_ `gdbstub` will clamp `count` to the size of it's packet buffer,
+    /// so targets may return less data than GDB requested.
+    fn pread(
+        &mut self,
+        fd: u32,
+        count: usize,
+        offset: u64,
+        buf: &mut [u8],
+    ) -> HostIoResult<usize, Self>;
+}
+
+define_ext!(HostIoPreadOps, HostIoPread);
+
+/// Nested Target Extension - Host I/O pwrite operation.
+pub trait HostIoPwrite: HostIo {
+    /// Write `data` to the file corresponding to `fd`, starting at `offset`
+    /// bytes into the file, and return the number of bytes written.
+    fn pwrite(&mut self, fd: u32, offset: u64, data: &[u8]) -> HostIoResult<usize, Self>;
+}
+
+define_ext!(HostIoPwriteOps, HostIoPwrite);
+
+/// Nested Target Extension - Host I/O fstat operation.
+pub trait HostIoFstat: HostIo {
+    /// Get information about the file corresponding to `fd`.
+    fn fstat(&mut self, fd: u32) -> HostIoResult<HostIoStat, Self>;
+}
+
+define_ext!(HostIoFstatOps, HostIoFstat);
+
+/// Nested Target Extension - Host I/O unlink operation.
+pub trait HostIoUnlink: HostIo {
+    /// Delete the file at `filename`.
+    fn unlink(&mut self, filename: &[u8]) -> HostIoResult<(), Self>;
+}
+
+define_ext!(HostIoUnlinkOps, HostIoUnlink);
+
+/// Nested Target Extension - Host I/O readlink operation.
+pub trait HostIoReadlink: HostIo {
+    /// Read the value of the symbolic link at `filename` into `buf`, and
+    /// return the number of bytes written.
+    ///
+    /// If `buf` is too small to hold the link's value, an error should be
+    /// returned (e.g: `ENAMETOOLONG`).
+    fn readlink(&mut self, filename: &[u8], buf: &mut [u8]) -> HostIoResult<usize, Self>;
+}
+
+define_ext!(HostIoReadlinkOps, HostIoReadlink);
+
+/// Nested Target Extension - Host I/O setfs operation.
+pub trait HostIoSetfs: HostIo {
+    /// Select the filesystem subsequent Host I/O operations should act on.
+    ///
+    /// If this operation isn't implemented, all Host I/O operations are
+    /// expected to act on the filesystem as seen by the remote stub.
+    fn setfs(&mut self, fs: FsKind) -> HostIoResult<(), Self>;
+}
+
+define_ext!(HostIoSetfsOps, HostIoSetfs);
@@ -0,0 +1,46 @@
+//! Report the libraries loaded by the target.
+//!
+//! The library list is used by GDB to determine which shared libraries are
+//! loaded (and where), enabling commands like `info sharedlibrary`, and
+//! resolving symbols in libraries which were relocated at load time.
+//!
+//! This is the preferred mechanism for reporting libraries on targets where
+//! the library list is maintained externally (e.g: Windows). System-V targets
+//! (e.g: Linux) should use [`LibrariesSvr4`](super::libraries_svr4) instead.
+
+use crate::arch::Arch;
+use crate::target::{Target, TargetResult};
+
+/// Describes where a library was loaded.
+#[derive(Debug, Clone, Copy)]
+pub enum LibraryAddrs<'a, U> {
+    /// Load addresses of each of the library's segments.
+    Segments(&'a [U]),
+    /// Load addresses of each of the library's allocatable sections.
+    Sections(&'a [U]),
+}
+
+/// A library loaded by the target.
+#[derive(Debug, Clone, Copy)]
+pub struct Library<'a, U> {
+    /// Path to the library's file.
+    pub name: &'a [u8],
+    /// Where the library was loaded.
+    pub addrs: LibraryAddrs<'a, U>,
+}
+
+/// Target Extension - Report the libraries loaded by the target.
+#[allow(clippy::type_complexity)]
+pub trait Libraries: Target {
+    /// Report each of the target's loaded libraries via the provided
+    /// callback.
+    ///
+    /// _Note:_ GDB may fetch the library list in multiple chunks, so the
+    /// libraries should be reported in a consistent order.
+    fn list_libraries(
+        &mut self,
+        register_library: &mut dyn FnMut(Library<'_, <Self::Arch as Arch>::Usize>),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(LibrariesOps, Libraries);
@@ -0,0 +1,53 @@
+//! Report the libraries loaded by a System-V (e.g: Linux) target.
+//!
+//! On System-V targets, GDB is capable of discovering the list of loaded
+//! libraries by walking the dynamic linker's `r_debug` / `link_map`
+//! structures, but doing so requires a _lot_ of memory reads. Reporting the
+//! library list directly is substantially faster.
+//!
+//! Each library is identified by the address of it's `link_map` entry (`lm`).
+//! When GDB already knows about part of the library list, it may request an
+//! incremental update, starting from a particular `link_map` entry.
+
+use crate::arch::Arch;
+use crate::target::{Target, TargetResult};
+
+/// A library loaded by the target.
+#[derive(Debug, Clone, Copy)]
+pub struct LibrarySvr4<'a, U> {
+    /// Path to the library's file.
+    pub name: &'a [u8],
+    /// Address of the library's `link_map` entry.
+    pub lm: U,
+    /// Load bias of the library (i.e: `link_map.l_addr`).
+    pub l_addr: U,
+    /// Address of the library's dynamic section (i.e: `link_map.l_ld`).
+    pub l_ld: U,
+}
+
+/// Target Extension - Report the libraries loaded by a System-V target.
+#[allow(clippy::type_complexity)]
+pub trait LibrariesSvr4: Target {
+    /// Return the address of the main executable's `link_map` entry (if
+    /// known).
+    fn main_lm(&mut self) -> TargetResult<Option<<Self::Arch as Arch>::Usize>, Self>;
+
+    /// Report each of the target's loaded libraries (in `link_map` order) via
+    /// the provided callback.
+    ///
+    /// When `start` is `Some`, only libraries starting from the `link_map`
+    /// entry at `start` should be reported. In this case, `prev` is the
+    /// address of the `link_map` entry preceding `start`, which can be used to
+    /// validate that GDB's view of the library list is still up to date.
+    ///
+    /// _Note:_ GDB may fetch the library list in multiple chunks, so the
+    /// libraries should be reported in a consistent order.
+    fn list_libraries_svr4(
+        &mut self,
+        start: Option<<Self::Arch as Arch>::Usize>,
+        prev: Option<<Self::Arch as Arch>::Usize>,
+        register_library: &mut dyn FnMut(LibrarySvr4<'_, <Self::Arch as Arch>::Usize>),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(LibrariesSvr4Ops, LibrariesSvr4);
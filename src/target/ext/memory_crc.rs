@@ -0,0 +1,41 @@
+//! Compute the CRC-32 of a region of target memory.
+//!
+//! GDB uses memory checksums to quickly verify that the contents of target
+//! memory match an ELF file's sections (e.g: when running
+//! `compare-sections`).
+//!
+//! `gdbstub` computes these checksums out of the box (using the target's
+//! `read_addrs` implementation), and as such, this extension only needs to be
+//! implemented by targets which can compute the checksum more efficiently
+//! (e.g: using a hardware CRC unit, or by hashing guest RAM in-place).
+
+use crate::arch::Arch;
+use crate::common::{Pid, Tid};
+use crate::target::{Target, TargetResult};
+
+/// Target Extension - Compute the CRC-32 of a region of target memory.
+pub trait MemoryCrc: Target {
+    /// Return the CRC-32 of the `len` bytes of memory starting at
+    /// `start_addr`, as seen by thread `tid` (in process `pid`).
+    ///
+    /// GDB uses a specific variant of CRC-32, which _must_ be matched exactly:
+    /// the polynomial is `0x04c11db7`, the checksum is seeded with
+    /// `0xffffffff`, each byte is processed most-significant bit first (i.e:
+    /// no bit reflection), and there is no final XOR.
+    ///
+    /// On targets which don't implement the multiprocess API, `pid` is always
+    /// set to an arbitrary placeholder value, and should be ignored.
+    /// Similarly, on single threaded targets, `tid` should be ignored.
+    ///
+    /// If the requested address range could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    fn crc32(
+        &mut self,
+        pid: Pid,
+        tid: Tid,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+    ) -> TargetResult<u32, Self>;
+}
+
+define_ext!(MemoryCrcOps, MemoryCrc);
@@ -0,0 +1,27 @@
+//! Provide a memory map for the target.
+//!
+//! A memory map describes which regions of the target's address space are
+//! RAM, ROM, or flash. GDB uses it to decide how to access memory (e.g: using
+//! hardware breakpoints in ROM, or flash programming commands when `load`ing
+//! into flash).
+//!
+//! _Note:_ Once a memory map has been provided, GDB will refuse to access any
+//! memory outside of the described regions (unless `mem inaccessible-by-default
+//! off` is set).
+
+use crate::target::Target;
+
+/// Target Extension - Provide a target memory map.
+pub trait MemoryMap: Target {
+    /// Return the target's memory map XML.
+    ///
+    /// Regions are described using `<memory type="ram|rom|flash">` elements,
+    /// with flash regions also specifying their erase block size (via a
+    /// `<property name="blocksize">` element).
+    ///
+    /// See the [GDB docs](https://sourceware.org/gdb/current/onlinedocs/gdb/Memory-Map-Format.html)
+    /// for details on the memory map XML format.
+    fn memory_map_xml(&self) -> &str;
+}
+
+define_ext!(MemoryMapOps, MemoryMap);
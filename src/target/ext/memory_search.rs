@@ -0,0 +1,39 @@
+//! Search target memory for a sequence of bytes.
+//!
+//! GDB uses memory searches to implement the `find` command.
+//!
+//! `gdbstub` searches target memory out of the box (using the target's
+//! `read_addrs` implementation), and as such, this extension only needs to be
+//! implemented by targets which can perform the search more efficiently (e.g:
+//! by scanning guest RAM in-place, instead of copying it out in chunks).
+
+use crate::arch::Arch;
+use crate::common::{Pid, Tid};
+use crate::target::{Target, TargetResult};
+
+/// Target Extension - Search target memory for a sequence of bytes.
+pub trait MemorySearch: Target {
+    /// Search the `len` bytes of memory starting at `start_addr` (as seen by
+    /// thread `tid` in process `pid`) for `pattern`, returning the address of
+    /// the first match (if any).
+    ///
+    /// Only matches which lie entirely within the search range should be
+    /// reported.
+    ///
+    /// On targets which don't implement the multiprocess API, `pid` is always
+    /// set to an arbitrary placeholder value, and should be ignored.
+    /// Similarly, on single threaded targets, `tid` should be ignored.
+    ///
+    /// If the requested address range could not be accessed, an appropriate
+    /// non-fatal error should be returned.
+    fn search_memory(
+        &mut self,
+        pid: Pid,
+        tid: Tid,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+        pattern: &[u8],
+    ) -> TargetResult<Option<<Self::Arch as Arch>::Usize>, Self>;
+}
+
+define_ext!(MemorySearchOps, MemorySearch);
@@ -0,0 +1,71 @@
+//! Provide tables of OS-level information (e.g: the list of running
+//! processes), which can be viewed via GDB's `info os` command.
+//!
+//! Each table is identified by a "type" (e.g: `processes`, or `files`), and
+//! consists of a series of rows, where each row is made up of a series of
+//! named columns. GDB doesn't enforce any particular schema, but for
+//! consistency with other debug stubs (e.g: `gdbserver`), it's recommended to
+//! use the same table / column names used on Linux, e.g:
+//!
+//! - `processes`: `pid`, `user`, `command`, `cores`
+//! - `files`: `pid`, `command`, `file descriptor`, `name`
+//!
+//! Running `info os` (without any arguments) lists the available tables.
+
+use crate::target::{Target, TargetResult};
+
+/// Describes a table of OS-level information.
+#[derive(Debug, Clone, Copy)]
+pub struct OsDataType<'a> {
+    /// The table's type, as passed to `info os <type>` (e.g: `"processes"`).
+    pub name: &'a str,
+    /// A short, human-readable title (e.g: `"Processes"`).
+    pub title: &'a str,
+    /// A description of the table (e.g: `"Listing of all processes"`).
+    pub description: &'a str,
+}
+
+/// The value of a single column in a row of OS-level information.
+#[derive(Debug, Clone, Copy)]
+pub enum OsDataValue<'a> {
+    /// A string.
+    Str(&'a [u8]),
+    /// An integer (displayed in decimal).
+    Int(u64),
+}
+
+/// A single named column in a row of OS-level information.
+#[derive(Debug, Clone, Copy)]
+pub struct OsDataColumn<'a> {
+    /// The column's name (e.g: `"pid"`).
+    pub name: &'a str,
+    /// The column's value.
+    pub value: OsDataValue<'a>,
+}
+
+/// Target Extension - Provide tables of OS-level information.
+#[allow(clippy::type_complexity)]
+pub trait OsData: Target {
+    /// Report each of the tables supported by the target via the provided
+    /// callback.
+    fn list_osdata_types(
+        &mut self,
+        register_type: &mut dyn FnMut(OsDataType<'_>),
+    ) -> TargetResult<(), Self>;
+
+    /// Report each row of the table `kind` (corresponding to one of the types
+    /// reported by `list_osdata_types`) via the provided callback.
+    ///
+    /// If the target doesn't support the requested table, a non-fatal error
+    /// should be returned.
+    ///
+    /// _Note:_ GDB may fetch the table in multiple chunks, so the rows should
+    /// be reported in a consistent order.
+    fn list_osdata(
+        &mut self,
+        kind: &[u8],
+        register_row: &mut dyn FnMut(&[OsDataColumn<'_>]),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(OsDataOps, OsData);
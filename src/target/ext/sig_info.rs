@@ -0,0 +1,52 @@
+//! Provide the signal information (i.e: `siginfo_t`) associated with a
+//! thread's most recent stop.
+//!
+//! GDB exposes this information to the user via the `$_siginfo` convenience
+//! variable (e.g: `p $_siginfo._sifields._sigfault.si_addr` to print the
+//! address which triggered a `SIGSEGV`).
+//!
+//! _Note:_ GDB interprets the raw bytes using the target OS's `siginfo_t`
+//! layout, and as such, this extension is typically only useful on Linux
+//! targets.
+
+use crate::common::{Pid, Tid};
+use crate::target::{Target, TargetResult};
+
+/// Target Extension - Provide signal information.
+pub trait SigInfo: Target {
+    /// Return the raw `siginfo_t` bytes associated with the most recent stop
+    /// of thread `tid` (in process `pid`).
+    ///
+    /// On targets which don't implement the multiprocess API, `pid` is always
+    /// set to an arbitrary placeholder value, and should be ignored.
+    fn get_siginfo(&mut self, pid: Pid, tid: Tid) -> TargetResult<&[u8], Self>;
+
+    /// Support for modifying the signal information.
+    fn support_write(&mut self) -> Option<SigInfoWriteOps<Self>> {
+        None
+    }
+}
+
+define_ext!(SigInfoOps, SigInfo);
+
+/// Nested Target Extension - Modify signal information.
+///
+/// Updated signal information is delivered to the thread alongside the
+/// signal when it is resumed (e.g: when running `set
+/// $_siginfo._sifields._sigfault.si_addr = 0`).
+pub trait SigInfoWrite: SigInfo {
+    /// Overwrite the raw `siginfo_t` bytes of thread `tid` (in process `pid`)
+    /// starting at `offset` with `data`, and return the number of bytes
+    /// written.
+    ///
+    /// Any data past the end of the `siginfo_t` struct should be ignored.
+    fn write_siginfo(
+        &mut self,
+        pid: Pid,
+        tid: Tid,
+        offset: usize,
+        data: &[u8],
+    ) -> TargetResult<usize, Self>;
+}
+
+define_ext!(SigInfoWriteOps, SigInfoWrite);
@@ -0,0 +1,196 @@
+//! Collect data from a running target using tracepoints.
+//!
+//! Tracepoints are similar to breakpoints, except that instead of stopping the
+//! target when they are hit, the target quickly collects some data (e.g:
+//! registers, memory, the result of an agent expression) into a "trace frame",
+//! and then keeps on running. Once the "trace experiment" has finished, GDB can
+//! select individual trace frames and inspect the data they contain.
+//!
+//! The overall flow looks something like this:
+//!
+//! 1. GDB clears any existing trace state (`tracepoints_init`).
+//! 2. GDB defines each tracepoint (`tracepoint_create`), followed by each of
+//!    the tracepoint's actions (`tracepoint_add_action`).
+//! 3. GDB starts the experiment (`trace_experiment_start`) and resumes the
+//!    target. Whenever an enabled tracepoint is hit (and it's condition
+//!    evaluates to true), the target should record a new trace frame.
+//! 4. GDB stops the experiment (`trace_experiment_stop`), and inspects the
+//!    collected data using `select_frame`.
+//!
+//! While a trace frame is selected, the target is expected to service _all_
+//! register and memory reads using the data from the selected frame (instead
+//! of the live target state). Data which wasn't collected should be reported
+//! as an error.
+//!
+//! Tracepoint conditions and `X` collection actions are sent as agent
+//! expression bytecode, which can be evaluated using the interpreter in the
+//! [`agent`](crate::agent) module.
+//!
+//! See the [GDB documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Tracepoints.html)
+//! for more details.
+
+use crate::arch::Arch;
+use crate::target::{Target, TargetResult};
+
+/// A tracepoint, identified by a GDB-assigned number.
+///
+/// _Note:_ A single tracepoint may have multiple locations (e.g: when
+/// tracing an inlined function), in which case GDB will create several
+/// tracepoints with the same number, each at a different address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tracepoint(pub usize);
+
+/// Describes a new tracepoint.
+#[derive(Debug)]
+pub struct NewTracepoint<'a, U> {
+    /// The tracepoint's number.
+    pub number: Tracepoint,
+    /// The address the tracepoint is located at.
+    pub addr: U,
+    /// Whether the tracepoint is enabled.
+    pub enabled: bool,
+    /// The number of single-steps to perform (and collect "while-stepping"
+    /// actions for) after the tracepoint is hit.
+    pub step_count: u64,
+    /// Stop the trace experiment once the tracepoint has been hit this many
+    /// times. A value of `0` means there is no limit.
+    pub pass_count: u64,
+    /// An agent expression which must evaluate to a non-zero value for the
+    /// tracepoint to collect any data.
+    pub condition: Option<&'a [u8]>,
+}
+
+/// An action to perform when a tracepoint is hit.
+#[derive(Debug)]
+pub enum TracepointAction<'a> {
+    /// Collect the registers set in `mask`.
+    ///
+    /// The mask is stored as a big-endian bitmask, where bit `n` corresponds
+    /// to the register with GDB register number `n` (i.e: the LSB of the
+    /// _last_ byte corresponds to register `0`).
+    Registers {
+        /// The register bitmask.
+        mask: &'a [u8],
+    },
+    /// Collect `length` bytes of memory.
+    Memory {
+        /// If present, the GDB register number whose value the `offset` is
+        /// relative to. If `None`, `offset` is an absolute address.
+        basereg: Option<u64>,
+        /// The offset (or address) of the memory to collect.
+        ///
+        /// _Note:_ relative offsets may be negative, and should be added to
+        /// the base register's value using wrapping arithmetic.
+        offset: u64,
+        /// The number of bytes to collect.
+        length: u64,
+    },
+    /// Evaluate an agent expression, collecting any data it requests (via the
+    /// various `trace` opcodes).
+    Expression {
+        /// The expression's bytecode.
+        expr: &'a [u8],
+    },
+}
+
+/// The state of the current trace experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExperimentState {
+    /// No trace experiment has been run yet.
+    NotRun,
+    /// The trace experiment is currently running.
+    Running,
+    /// The trace experiment was stopped by GDB.
+    Stopped,
+    /// The trace experiment was stopped because the trace buffer filled up.
+    BufferFull,
+    /// The trace experiment was stopped because the specified tracepoint
+    /// reached it's pass count.
+    PassCount(Tracepoint),
+}
+
+/// The status of the current trace experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExperimentStatus {
+    /// The state of the trace experiment.
+    pub state: ExperimentState,
+    /// The number of trace frames currently in the trace buffer.
+    pub frames: u64,
+    /// The total number of trace frames created during the experiment
+    /// (including any which may have been discarded).
+    pub created: u64,
+}
+
+/// Selects a trace frame to inspect.
+///
+/// With the exception of `Number`, each request should find the _next_ frame
+/// (i.e: the first frame after the currently selected frame, or the first
+/// frame in the trace buffer if no frame is currently selected) which matches
+/// the criteria.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameRequest<U> {
+    /// Select the frame with the given number.
+    Number(u64),
+    /// Select a frame whose PC is at the given address.
+    AtPc(U),
+    /// Select a frame which was collected by the given tracepoint.
+    Hit(Tracepoint),
+    /// Select a frame whose PC is within the given range (inclusive).
+    Between(U, U),
+    /// Select a frame whose PC is outside the given range (inclusive).
+    Outside(U, U),
+    /// Stop inspecting trace frames, returning to the live target state.
+    Exit,
+}
+
+/// Target Extension - Support tracepoints.
+///
+/// Corresponds to the `QTinit`, `QTDP`, `QTStart`, `QTStop`, `qTStatus` and
+/// `QTFrame` packets. See the [tracepoints module documentation](index.html).
+pub trait Tracepoints: Target {
+    /// Clear all existing tracepoints and trace frames, in preparation for a
+    /// new trace experiment.
+    fn tracepoints_init(&mut self) -> TargetResult<(), Self>;
+
+    /// Create a new tracepoint.
+    fn tracepoint_create(
+        &mut self,
+        tp: NewTracepoint<'_, <Self::Arch as Arch>::Usize>,
+    ) -> TargetResult<(), Self>;
+
+    /// Add an action to the tracepoint `tp` located at `addr`.
+    ///
+    /// If `while_stepping` is true, the action should be performed after each
+    /// of the single-steps following a tracepoint hit (as specified by the
+    /// tracepoint's `step_count`), instead of when the tracepoint is hit.
+    fn tracepoint_add_action(
+        &mut self,
+        tp: Tracepoint,
+        addr: <Self::Arch as Arch>::Usize,
+        while_stepping: bool,
+        action: TracepointAction<'_>,
+    ) -> TargetResult<(), Self>;
+
+    /// Start a new trace experiment.
+    fn trace_experiment_start(&mut self) -> TargetResult<(), Self>;
+
+    /// Stop the current trace experiment.
+    fn trace_experiment_stop(&mut self) -> TargetResult<(), Self>;
+
+    /// Report the status of the current trace experiment.
+    fn trace_experiment_status(&mut self) -> TargetResult<ExperimentStatus, Self>;
+
+    /// Select a trace frame to inspect.
+    ///
+    /// Returns the selected frame's number, along with the tracepoint which
+    /// collected it, or `None` if no matching frame could be found (in which
+    /// case no frame should be selected).
+    ///
+    /// The return value is ignored for `FrameRequest::Exit`.
+    fn select_frame(
+        &mut self,
+        frame: FrameRequest<<Self::Arch as Arch>::Usize>,
+    ) -> TargetResult<Option<(u64, Tracepoint)>, Self>;
+}
+
+define_ext!(TracepointsOps, Tracepoints);
@@ -0,0 +1,34 @@
+/// Update a CRC-32 checksum with the contents of `data`, using the same
+/// variant of CRC-32 as GDB (i.e: polynomial `0x04c11db7`, with no bit
+/// reflection and no final XOR).
+///
+/// GDB seeds the checksum with `0xffffffff`.
+pub fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    for &b in data {
+        crc ^= (b as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04c1_1db7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(0xffff_ffff, b"123456789"), 0x0376_e6e7);
+    }
+
+    #[test]
+    fn crc32_incremental() {
+        let crc = crc32(0xffff_ffff, b"1234");
+        assert_eq!(crc32(crc, b"56789"), 0x0376_e6e7);
+    }
+}
@@ -1 +1,2 @@
+pub mod crc32;
 pub mod managed_vec;